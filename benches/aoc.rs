@@ -0,0 +1,45 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use narigama_aoc::history::{bench_group, bench_name};
+
+/// bench every enabled day: parsing, then each part of each variant, grouped
+/// as `yYYYY/dNN` so a year or day can be picked out with a filter
+fn criterion_benchmark(c: &mut Criterion) {
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .try_init()
+        .ok();
+
+    for (_, solutions) in narigama_aoc::YEARS {
+        for solution in solutions() {
+            let name = bench_group(solution.year, solution.day);
+
+            // a missing input shouldn't take every other day down with it
+            let input = match solution.parse() {
+                Ok(input) => input,
+                Err(e) => {
+                    tracing::warn!("skipping {name}, unable to parse its input: {e}");
+                    continue;
+                }
+            };
+
+            let mut group = c.benchmark_group(&name);
+
+            group.bench_function("parse", |b| b.iter(|| solution.parse().unwrap()));
+
+            for variant in solution.variants() {
+                group.bench_function(bench_name("p01", variant.name), |b| {
+                    b.iter(|| variant.part_one(black_box(input.as_ref())).unwrap())
+                });
+
+                group.bench_function(bench_name("p02", variant.name), |b| {
+                    b.iter(|| variant.part_two(black_box(input.as_ref())).unwrap())
+                });
+            }
+
+            group.finish();
+        }
+    }
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -1,10 +1,240 @@
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use narigama_aoc::{
+    history::{self, History, Run},
+    runner::{self, DEFAULT_VARIANT},
+    visualize::Player,
+};
+
+const USAGE: &str = "usage: narigama_aoc [<year>] [--json] [--threshold <ms>] \
+    [--variant <name>] [--check] [--visualize [--fps <n>] [--step <n>]] [--export <dir>] \
+    | bench-report [<year>] [--criterion <dir>] [--regression <percent>] [--history <path>] \
+    [--save [--force]] | new <year> <day>\n\n\
+    bench-report reads the medians of the last `cargo bench`, run that first";
+
+/// remove a flag from args, returning whether it was present
+fn take_flag(args: &mut Vec<String>, flag: &str) -> bool {
+    let found = args.iter().any(|a| a == flag);
+    args.retain(|a| a != flag);
+    found
+}
+
+/// remove an option and its value from args
+fn take_option(args: &mut Vec<String>, option: &str) -> eyre::Result<Option<String>> {
+    let Some(index) = args.iter().position(|a| a == option) else {
+        return Ok(None);
+    };
+
+    eyre::ensure!(index + 1 < args.len(), "{option} requires a value");
+    let value = args.remove(index + 1);
+    args.remove(index);
+
+    Ok(Some(value))
+}
+
 fn main() -> eyre::Result<()> {
-    // setup env and logging
+    // setup env and logging, logs go to stderr so stdout can be piped
     dotenv::dotenv().ok();
-    tracing_subscriber::fmt::init();
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .init();
+
+    let mut args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    let json = take_flag(&mut args, "--json");
+
+    // flag any day that takes longer than this, defaults to a second
+    let threshold = match take_option(&mut args, "--threshold")? {
+        Some(ms) => Duration::from_millis(ms.parse()?),
+        None => Duration::from_secs(1),
+    };
+
+    // run a named implementation where a day has one
+    let variant = take_option(&mut args, "--variant")?;
+
+    // make sure every day's variants agree before running anything
+    let check = take_flag(&mut args, "--check");
+
+    // animate any day with something to show, only when stdout is a terminal
+    let visualize = take_flag(&mut args, "--visualize");
+    let mut player = Player::default();
+    if let Some(fps) = take_option(&mut args, "--fps")? {
+        player.fps = fps.parse()?;
+    }
+    if let Some(step) = take_option(&mut args, "--step")? {
+        player.step = step.parse()?;
+    }
+
+    // write out any images a day can draw
+    let export = take_option(&mut args, "--export")?.map(PathBuf::from);
+
+    // time every day and compare against the last recorded run
+    let bench_report = args.first().is_some_and(|a| a == "bench-report");
+    if bench_report {
+        args.remove(0);
+    }
+    let criterion = match take_option(&mut args, "--criterion")? {
+        Some(dir) => PathBuf::from(dir),
+        None => std::env::var("CARGO_TARGET_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("target"))
+            .join("criterion"),
+    };
+    // percent slower than the baseline before a day counts as a regression
+    let regression = match take_option(&mut args, "--regression")? {
+        Some(percent) => percent.parse()?,
+        None => 25.0,
+    };
+    let history_path = match take_option(&mut args, "--history")? {
+        Some(path) => PathBuf::from(path),
+        None => PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(history::HISTORY_PATH),
+    };
+    let save = take_flag(&mut args, "--save");
+
+    // record a run even if it regressed, making it the new baseline
+    let force = take_flag(&mut args, "--force");
+
+    let solutions = match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        // scaffold a new day
+        ["new", year, day] if !bench_report => {
+            return narigama_aoc::scaffold::new_day(year.parse()?, day.parse()?);
+        }
+
+        // run a single year
+        [year] => {
+            let year: u64 = year.parse()?;
+            let (_, solutions) = narigama_aoc::YEARS
+                .iter()
+                .find(|(y, _)| *y == year)
+                .ok_or_else(|| eyre::eyre!("{year} has no solutions"))?;
+
+            solutions()
+        }
+
+        // run every year
+        [] => narigama_aoc::YEARS
+            .iter()
+            .flat_map(|(_, solutions)| solutions())
+            .collect(),
+
+        _ => eyre::bail!(USAGE),
+    };
+
+    // a typo shouldn't quietly run every default instead
+    if let Some(name) = &variant {
+        eyre::ensure!(
+            solutions.iter().any(|s| s.variant(name).is_some()),
+            "no selected day has a variant named `{name}`"
+        );
+    }
+
+    if check {
+        for solution in solutions.iter().filter(|s| s.variants().len() > 1) {
+            solution.check_variants()?;
+            tracing::info!(
+                "{}/{:0>2}: {} variant(s) agree",
+                solution.year,
+                solution.day,
+                solution.variants().len()
+            );
+        }
+    }
+
+    if bench_report {
+        return report(
+            &solutions,
+            &criterion,
+            regression,
+            &history_path,
+            save,
+            force,
+        );
+    }
+
+    if let Some(dir) = &export {
+        for solution in &solutions {
+            for path in solution.export(dir)? {
+                tracing::info!("wrote {}", path.display());
+            }
+        }
+    }
+
+    // animations play on the same input each day was solved with
+    let reports = solutions
+        .iter()
+        .map(|s| {
+            let name = variant
+                .as_deref()
+                .filter(|v| s.variant(v).is_some())
+                .unwrap_or(DEFAULT_VARIANT);
+
+            match visualize {
+                true => s.run_visualized(name, &player),
+                false => s.run_variant(name),
+            }
+        })
+        .collect::<eyre::Result<Vec<_>>>()?;
+
+    match json {
+        true => println!("{}", runner::summary_json(&reports, threshold)?),
+        false => println!("\n{}", runner::summary_table(&reports, threshold)),
+    }
+
+    Ok(())
+}
+
+/// print a markdown table of criterion's medians against the last recorded
+/// run, failing if any day got slower by more than `regression` percent. A
+/// regressed run is only saved when forced.
+fn report(
+    solutions: &[runner::Solution],
+    criterion: &Path,
+    regression: f64,
+    path: &Path,
+    save: bool,
+    force: bool,
+) -> eyre::Result<()> {
+    let mut history = History::load(path)?;
+    let current = Run::from_criterion(criterion, solutions)?;
+
+    let comparisons = history::compare(&current, history.baseline());
+    println!("{}", history::markdown(&comparisons, regression));
+
+    let regressed = comparisons
+        .iter()
+        .filter(|c| c.regressed(regression))
+        .collect::<Vec<_>>();
+    for comparison in &regressed {
+        let timing = comparison.current;
+        tracing::warn!(
+            "{}/{:0>2} ({}) regressed by {:.1}%",
+            timing.year,
+            timing.day,
+            timing.variant,
+            comparison.change().unwrap_or_default()
+        );
+    }
+    let regressed = regressed.len();
 
-    // run years
-    narigama_aoc::y2022::main()?;
+    if save {
+        match regressed == 0 || force {
+            true => {
+                history.runs.push(current);
+                history.save(path)?;
+                tracing::info!("recorded run {} in {}", history.runs.len(), path.display());
+            }
+            false => tracing::warn!(
+                "not recording a run that regressed, pass --force to record it anyway"
+            ),
+        }
+    }
 
+    eyre::ensure!(
+        regressed == 0,
+        "{regressed} day(s) regressed by more than {regression}%"
+    );
     Ok(())
 }
@@ -0,0 +1,507 @@
+use std::{collections::HashMap, path::Path, time::Duration};
+
+use serde_json::{json, Value};
+
+use crate::runner::{Solution, DEFAULT_VARIANT};
+
+/// bumped whenever the layout of the history file changes
+pub const VERSION: u64 = 1;
+
+/// where `bench-report --save` keeps its runs, relative to the repo
+pub const HISTORY_PATH: &str = "benches/history.json";
+
+/// the criterion group a day's benches are in, `yYYYY/dNN`
+pub fn bench_group(year: u64, day: u64) -> String {
+    format!("y{year}/d{day:0>2}")
+}
+
+/// a bench within a day's group, `part` suffixed with any non-default variant
+pub fn bench_name(part: &str, variant: &str) -> String {
+    match variant {
+        DEFAULT_VARIANT => part.to_owned(),
+        other => format!("{part}/{other}"),
+    }
+}
+
+/// a day's median timings, as criterion last measured them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timing {
+    pub year: u64,
+    pub day: u64,
+    pub variant: String,
+    pub parse: Duration,
+    pub part_one: Duration,
+    pub part_two: Duration,
+}
+
+impl Timing {
+    pub fn total(&self) -> Duration {
+        self.parse + self.part_one + self.part_two
+    }
+
+    fn key(&self) -> (u64, u64, &str) {
+        (self.year, self.day, &self.variant)
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "year": self.year,
+            "day": self.day,
+            "variant": self.variant,
+            "parse_ns": self.parse.as_nanos() as u64,
+            "part_one_ns": self.part_one.as_nanos() as u64,
+            "part_two_ns": self.part_two.as_nanos() as u64,
+        })
+    }
+
+    fn from_json(value: &Value) -> eyre::Result<Self> {
+        let number = |key: &str| {
+            value[key]
+                .as_u64()
+                .ok_or_else(|| eyre::eyre!("timing is missing `{key}`"))
+        };
+        let nanos = |key: &str| number(key).map(Duration::from_nanos);
+
+        Ok(Self {
+            year: number("year")?,
+            day: number("day")?,
+            variant: value["variant"]
+                .as_str()
+                .unwrap_or(DEFAULT_VARIANT)
+                .to_owned(),
+            parse: nanos("parse_ns")?,
+            part_one: nanos("part_one_ns")?,
+            part_two: nanos("part_two_ns")?,
+        })
+    }
+}
+
+/// one recorded `bench-report` run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Run {
+    // seconds since the unix epoch
+    pub recorded: u64,
+
+    // the commit that was measured, if it could be found
+    pub commit: Option<String>,
+    pub timings: Vec<Timing>,
+}
+
+impl Run {
+    /// the medians from criterion's last `cargo bench`, read from `dir`
+    /// (usually `target/criterion`). Days without results are skipped.
+    pub fn from_criterion(dir: &Path, solutions: &[Solution]) -> eyre::Result<Self> {
+        let medians = criterion_medians(dir)?;
+        let mut timings = Vec::new();
+
+        for solution in solutions {
+            let group = bench_group(solution.year, solution.day);
+            let median = |bench: &str| medians.get(&format!("{group}/{bench}")).copied();
+
+            for variant in solution.variants() {
+                let parts = (
+                    median("parse"),
+                    median(&bench_name("p01", variant.name)),
+                    median(&bench_name("p02", variant.name)),
+                );
+
+                match parts {
+                    (Some(parse), Some(part_one), Some(part_two)) => timings.push(Timing {
+                        year: solution.year,
+                        day: solution.day,
+                        variant: variant.name.to_owned(),
+                        parse,
+                        part_one,
+                        part_two,
+                    }),
+                    _ => tracing::warn!(
+                        "skipping {group} ({}), it hasn't been benched",
+                        variant.name
+                    ),
+                }
+            }
+        }
+
+        eyre::ensure!(
+            !timings.is_empty(),
+            "no bench results in {}, run `cargo bench` first",
+            dir.display()
+        );
+
+        Ok(Self {
+            recorded: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)?
+                .as_secs(),
+            commit: commit(),
+            timings,
+        })
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "recorded": self.recorded,
+            "commit": self.commit,
+            "timings": self.timings.iter().map(Timing::to_json).collect::<Vec<_>>(),
+        })
+    }
+
+    fn from_json(value: &Value) -> eyre::Result<Self> {
+        Ok(Self {
+            recorded: value["recorded"]
+                .as_u64()
+                .ok_or_else(|| eyre::eyre!("run is missing `recorded`"))?,
+            commit: value["commit"].as_str().map(str::to_owned),
+            timings: value["timings"]
+                .as_array()
+                .ok_or_else(|| eyre::eyre!("run is missing `timings`"))?
+                .iter()
+                .map(Timing::from_json)
+                .collect::<eyre::Result<_>>()?,
+        })
+    }
+}
+
+/// every recorded run, oldest first
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct History {
+    pub runs: Vec<Run>,
+}
+
+impl History {
+    /// read a history file, a missing file is an empty history
+    pub fn load(path: impl AsRef<Path>) -> eyre::Result<Self> {
+        let path = path.as_ref();
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let value: Value = serde_json::from_str(&text)?;
+        let version = value["version"]
+            .as_u64()
+            .ok_or_else(|| eyre::eyre!("{} has no version", path.display()))?;
+        eyre::ensure!(
+            version <= VERSION,
+            "{} is version {version}, only up to {VERSION} is understood",
+            path.display()
+        );
+
+        let runs = value["runs"]
+            .as_array()
+            .ok_or_else(|| eyre::eyre!("{} has no runs", path.display()))?
+            .iter()
+            .enumerate()
+            .map(|(i, run)| Run::from_json(run).map_err(|e| eyre::eyre!("run {i}: {e}")))
+            .collect::<eyre::Result<_>>()?;
+
+        Ok(Self { runs })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> eyre::Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let json = serde_json::to_string_pretty(&json!({
+            "version": VERSION,
+            "runs": self.runs.iter().map(Run::to_json).collect::<Vec<_>>(),
+        }))?;
+
+        Ok(std::fs::write(path, json + "\n")?)
+    }
+
+    /// the run everything is compared against
+    pub fn baseline(&self) -> Option<&Run> {
+        self.runs.last()
+    }
+}
+
+/// a day's timing against its baseline
+#[derive(Debug, Clone)]
+pub struct Comparison<'a> {
+    pub current: &'a Timing,
+    pub baseline: Option<&'a Timing>,
+}
+
+impl Comparison<'_> {
+    /// how much slower the total is as a percentage, negative when faster
+    pub fn change(&self) -> Option<f64> {
+        let baseline = self.baseline?.total().as_secs_f64();
+        match baseline > 0.0 {
+            true => Some((self.current.total().as_secs_f64() / baseline - 1.0) * 100.0),
+            false => None,
+        }
+    }
+
+    /// slower than the baseline by more than `threshold` percent
+    pub fn regressed(&self, threshold: f64) -> bool {
+        self.change().is_some_and(|change| change > threshold)
+    }
+}
+
+/// pair each current timing with the same day and variant in the baseline
+pub fn compare<'a>(current: &'a Run, baseline: Option<&'a Run>) -> Vec<Comparison<'a>> {
+    current
+        .timings
+        .iter()
+        .map(|timing| Comparison {
+            current: timing,
+            baseline: baseline.and_then(|b| b.timings.iter().find(|t| t.key() == timing.key())),
+        })
+        .collect()
+}
+
+/// a markdown table of timings, with the change against the baseline
+pub fn markdown(comparisons: &[Comparison<'_>], threshold: f64) -> String {
+    let mut lines = vec![
+        "| day | parse | part one | part two | total | change |".to_owned(),
+        "| --- | ---: | ---: | ---: | ---: | ---: |".to_owned(),
+    ];
+
+    for comparison in comparisons {
+        let timing = comparison.current;
+        let day = match timing.variant.as_str() {
+            DEFAULT_VARIANT => format!("{}/{:0>2}", timing.year, timing.day),
+            variant => format!("{}/{:0>2} ({variant})", timing.year, timing.day),
+        };
+        let change = match comparison.change() {
+            Some(change) if comparison.regressed(threshold) => format!("**{change:+.1}%**"),
+            Some(change) => format!("{change:+.1}%"),
+            None => "new".to_owned(),
+        };
+
+        lines.push(format!(
+            "| {day} | {:.2?} | {:.2?} | {:.2?} | {:.2?} | {change} |",
+            timing.parse,
+            timing.part_one,
+            timing.part_two,
+            timing.total(),
+        ));
+    }
+
+    lines.join("\n")
+}
+
+/// every bench's median by its full id, e.g. `y2022/d06/p01/naive`. Criterion
+/// keeps each bench's latest estimates in `<group>/<bench>/new`.
+fn criterion_medians(dir: &Path) -> eyre::Result<HashMap<String, Duration>> {
+    let mut medians = HashMap::new();
+    if !dir.is_dir() {
+        return Ok(medians);
+    }
+
+    for group in std::fs::read_dir(dir)? {
+        let group = group?.path();
+        if !group.is_dir() {
+            continue;
+        }
+
+        for bench in std::fs::read_dir(&group)? {
+            let latest = bench?.path().join("new");
+            let (Ok(benchmark), Ok(estimates)) = (
+                std::fs::read_to_string(latest.join("benchmark.json")),
+                std::fs::read_to_string(latest.join("estimates.json")),
+            ) else {
+                continue;
+            };
+
+            let benchmark: Value = serde_json::from_str(&benchmark)?;
+            let estimates: Value = serde_json::from_str(&estimates)?;
+            let (Some(id), Some(nanos)) = (
+                benchmark["full_id"].as_str(),
+                estimates["median"]["point_estimate"].as_f64(),
+            ) else {
+                eyre::bail!("unexpected criterion output in {}", latest.display());
+            };
+
+            medians.insert(id.to_owned(), Duration::from_secs_f64(nanos / 1e9));
+        }
+    }
+
+    Ok(medians)
+}
+
+/// the short hash of HEAD, if this is a git checkout
+fn commit() -> Option<String> {
+    let output = std::process::Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()?;
+
+    match output.status.success() {
+        true => Some(String::from_utf8_lossy(&output.stdout).trim().to_owned()),
+        false => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// an empty directory under the system temp dir, unique to the test
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "narigama-aoc-history-{}-{name}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn timing(day: u64, variant: &str, secs: u64) -> Timing {
+        Timing {
+            year: 2022,
+            day,
+            variant: variant.to_owned(),
+            parse: Duration::from_secs(secs),
+            part_one: Duration::ZERO,
+            part_two: Duration::ZERO,
+        }
+    }
+
+    fn run(timings: Vec<Timing>) -> Run {
+        Run {
+            recorded: 1_700_000_000,
+            commit: Some("abc1234".into()),
+            timings,
+        }
+    }
+
+    /// write criterion's files for a single bench
+    fn bench(dir: &Path, full_id: &str, median_ns: f64) {
+        let latest = dir
+            .join(full_id.replace('/', "_"))
+            .join("bench")
+            .join("new");
+        std::fs::create_dir_all(&latest).unwrap();
+        std::fs::write(
+            latest.join("benchmark.json"),
+            json!({ "full_id": full_id }).to_string(),
+        )
+        .unwrap();
+        std::fs::write(
+            latest.join("estimates.json"),
+            json!({ "median": { "point_estimate": median_ns } }).to_string(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let dir = scratch("round-trip");
+        let path = dir.join("nested/history.json");
+        let history = History {
+            runs: vec![
+                run(vec![timing(1, DEFAULT_VARIANT, 3)]),
+                Run {
+                    commit: None,
+                    ..run(vec![timing(1, DEFAULT_VARIANT, 2), timing(6, "naive", 5)])
+                },
+            ],
+        };
+
+        history.save(&path).unwrap();
+        assert_eq!(History::load(&path).unwrap(), history);
+        assert_eq!(history.baseline(), history.runs.last());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn load_checks_the_version() {
+        let dir = scratch("version");
+        assert_eq!(
+            History::load(dir.join("missing.json")).unwrap(),
+            History::default()
+        );
+
+        let path = dir.join("history.json");
+        std::fs::write(
+            &path,
+            json!({ "version": VERSION + 1, "runs": [] }).to_string(),
+        )
+        .unwrap();
+        let error = History::load(&path).unwrap_err();
+        assert!(error.to_string().contains("only up to"), "{error}");
+
+        std::fs::write(&path, json!({ "runs": [] }).to_string()).unwrap();
+        assert!(History::load(&path).is_err());
+
+        std::fs::write(
+            &path,
+            json!({ "version": VERSION, "runs": [{}] }).to_string(),
+        )
+        .unwrap();
+        let error = History::load(&path).unwrap_err();
+        assert!(error.to_string().starts_with("run 0:"), "{error}");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn compare_pairs_days_and_variants() {
+        let baseline = run(vec![
+            timing(1, DEFAULT_VARIANT, 100),
+            timing(6, "naive", 100),
+        ]);
+        let current = run(vec![
+            timing(1, DEFAULT_VARIANT, 125),
+            timing(6, "naive", 126),
+            timing(6, DEFAULT_VARIANT, 1),
+        ]);
+
+        let comparisons = compare(&current, Some(&baseline));
+        assert_eq!(comparisons[0].change(), Some(25.0));
+        assert_eq!(comparisons[2].baseline, None);
+        assert_eq!(comparisons[2].change(), None);
+
+        // exactly at the threshold isn't a regression, just over it is
+        assert!(!comparisons[0].regressed(25.0));
+        assert!(comparisons[1].regressed(25.0));
+        assert!(!comparisons[2].regressed(25.0));
+
+        assert!(compare(&current, None).iter().all(|c| c.baseline.is_none()));
+
+        let table = markdown(&comparisons, 25.0);
+        assert!(table.contains("| 2022/06 (naive) |"), "{table}");
+        assert!(table.contains("**+26.0%**"), "{table}");
+        assert!(table.ends_with("| new |"), "{table}");
+    }
+
+    #[test]
+    fn medians_come_from_the_latest_estimates() {
+        let dir = scratch("criterion");
+        bench(&dir, "y2022/d06/parse", 1500.0);
+        bench(&dir, "y2022/d06/p01/naive", 2_000_000.0);
+
+        // criterion's own report folder has no estimates
+        std::fs::create_dir_all(dir.join("report")).unwrap();
+
+        let medians = criterion_medians(&dir).unwrap();
+        assert_eq!(medians.len(), 2);
+        assert_eq!(medians["y2022/d06/parse"], Duration::from_nanos(1500));
+        assert_eq!(medians["y2022/d06/p01/naive"], Duration::from_millis(2));
+
+        assert!(criterion_medians(&dir.join("missing")).unwrap().is_empty());
+
+        // an estimate without a median isn't something to guess at
+        let latest = dir.join("broken/bench/new");
+        std::fs::create_dir_all(&latest).unwrap();
+        std::fs::write(latest.join("benchmark.json"), r#"{"full_id": "x"}"#).unwrap();
+        std::fs::write(latest.join("estimates.json"), "{}").unwrap();
+        assert!(criterion_medians(&dir).is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn bench_ids_name_the_variant() {
+        assert_eq!(bench_group(2022, 6), "y2022/d06");
+        assert_eq!(bench_name("p01", DEFAULT_VARIANT), "p01");
+        assert_eq!(bench_name("p01", "naive"), "p01/naive");
+    }
+}
@@ -1,2 +1,12 @@
+pub mod history;
+pub mod runner;
+pub mod scaffold;
 pub mod util;
+pub mod visualize;
 pub mod y2022;
+
+/// lists every enabled day of a year
+pub type Solutions = fn() -> Vec<runner::Solution>;
+
+/// every year with solutions
+pub const YEARS: &[(u64, Solutions)] = &[(2022, y2022::solutions)];
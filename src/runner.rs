@@ -0,0 +1,403 @@
+use std::{
+    any::Any,
+    fmt::Display,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use serde_json::json;
+
+use crate::{
+    util::image::Exports,
+    visualize::{Frames, Player},
+};
+
+type Input = Box<dyn Any>;
+type Parse = Box<dyn Fn() -> eyre::Result<Input>>;
+type Part = Box<dyn Fn(&dyn Any) -> eyre::Result<String>>;
+type FrameHook = Box<dyn for<'a> Fn(&'a dyn Any) -> eyre::Result<Frames<'a>>>;
+type ExportHook = Box<dyn Fn(&dyn Any) -> eyre::Result<Exports>>;
+
+/// the name of the implementation passed to `Solution::new`
+pub const DEFAULT_VARIANT: &str = "default";
+
+/// a named implementation of both parts
+pub struct Variant {
+    pub name: &'static str,
+    part_one: Part,
+    part_two: Part,
+}
+
+impl Variant {
+    pub fn part_one(&self, input: &dyn Any) -> eyre::Result<String> {
+        (self.part_one)(input)
+    }
+
+    pub fn part_two(&self, input: &dyn Any) -> eyre::Result<String> {
+        (self.part_two)(input)
+    }
+}
+
+/// a day's parser and every implementation of its parts, with the input type
+/// erased so every day can be stored and run the same way
+pub struct Solution {
+    pub year: u64,
+    pub day: u64,
+    parse: Parse,
+    variants: Vec<Variant>,
+    frames: Option<FrameHook>,
+    exports: Option<ExportHook>,
+}
+
+/// hide the input type behind `Any`, rendering the answer to a string
+fn erase<I: 'static, A: Display + 'static>(part: fn(&I) -> eyre::Result<A>) -> Part {
+    Box::new(move |input| {
+        let input = input
+            .downcast_ref::<I>()
+            .ok_or_else(|| eyre::eyre!("input was not the type the part expected"))?;
+
+        Ok(part(input)?.to_string())
+    })
+}
+
+impl Solution {
+    pub fn new<I, A, B>(
+        year: u64,
+        day: u64,
+        parse: fn() -> eyre::Result<I>,
+        part_one: fn(&I) -> eyre::Result<A>,
+        part_two: fn(&I) -> eyre::Result<B>,
+    ) -> Self
+    where
+        I: 'static,
+        A: Display + 'static,
+        B: Display + 'static,
+    {
+        Self {
+            year,
+            day,
+            parse: Box::new(move || Ok(Box::new(parse()?) as Input)),
+            variants: vec![Variant {
+                name: DEFAULT_VARIANT,
+                part_one: erase(part_one),
+                part_two: erase(part_two),
+            }],
+            frames: None,
+            exports: None,
+        }
+    }
+
+    /// register another implementation of both parts, taking the same input
+    pub fn with_variant<I, A, B>(
+        mut self,
+        name: &'static str,
+        part_one: fn(&I) -> eyre::Result<A>,
+        part_two: fn(&I) -> eyre::Result<B>,
+    ) -> Self
+    where
+        I: 'static,
+        A: Display + 'static,
+        B: Display + 'static,
+    {
+        self.variants.push(Variant {
+            name,
+            part_one: erase(part_one),
+            part_two: erase(part_two),
+        });
+        self
+    }
+
+    /// every implementation, the default first
+    pub fn variants(&self) -> &[Variant] {
+        &self.variants
+    }
+
+    pub fn variant(&self, name: &str) -> Option<&Variant> {
+        self.variants.iter().find(|v| v.name == name)
+    }
+
+    pub fn parse(&self) -> eyre::Result<Box<dyn Any>> {
+        (self.parse)()
+    }
+
+    /// run every variant on the same input, failing if any answer differs
+    /// from the default's
+    pub fn check_variants(&self) -> eyre::Result<()> {
+        let input = (self.parse)()?;
+        let answers = |v: &Variant| -> eyre::Result<_> {
+            Ok((v.part_one(input.as_ref())?, v.part_two(input.as_ref())?))
+        };
+
+        // SAFETY: `new` always registers the default
+        let (default, rest) = self.variants.split_first().unwrap();
+        let expected = answers(default)?;
+
+        for variant in rest {
+            let actual = answers(variant)?;
+            eyre::ensure!(
+                actual == expected,
+                "{}/{:0>2}: `{}` answered {actual:?}, but `{}` answered {expected:?}",
+                self.year,
+                self.day,
+                variant.name,
+                default.name
+            );
+        }
+
+        Ok(())
+    }
+
+    /// attach something worth watching, see `visualize`
+    pub fn with_frames<I: 'static>(
+        mut self,
+        frames: for<'a> fn(&'a I) -> eyre::Result<Frames<'a>>,
+    ) -> Self {
+        self.frames = Some(Box::new(move |input| {
+            let input = input
+                .downcast_ref::<I>()
+                .ok_or_else(|| eyre::eyre!("input was not the type the frames expected"))?;
+
+            frames(input)
+        }));
+        self
+    }
+
+    /// parse the input and play the day's frames, if it has any and there's
+    /// a terminal to play them on
+    pub fn visualize(&self, player: &Player) -> eyre::Result<()> {
+        if self.frames.is_none() || !player.can_play() {
+            return Ok(());
+        }
+
+        let input = (self.parse)()?;
+        self.play(input.as_ref(), player)
+    }
+
+    /// as `visualize`, on an input that's already been parsed
+    fn play(&self, input: &dyn Any, player: &Player) -> eyre::Result<()> {
+        match (&self.frames, player.can_play()) {
+            (Some(frames), true) => {
+                player.play(&format!("{}/{:0>2}", self.year, self.day), frames(input)?)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// attach images or data worth keeping, each named so it can be saved,
+    /// see `export`
+    pub fn with_export<I: 'static>(mut self, exports: fn(&I) -> eyre::Result<Exports>) -> Self {
+        self.exports = Some(Box::new(move |input| {
+            let input = input
+                .downcast_ref::<I>()
+                .ok_or_else(|| eyre::eyre!("input was not the type the exports expected"))?;
+
+            exports(input)
+        }));
+        self
+    }
+
+    /// parse the input and save the day's exports into `dir` as
+    /// `YYYY-DD-name.png`, `.gif` and so on, returning every path written
+    pub fn export(&self, dir: &Path) -> eyre::Result<Vec<PathBuf>> {
+        let Some(exports) = &self.exports else {
+            return Ok(Vec::new());
+        };
+
+        std::fs::create_dir_all(dir)?;
+        let input = (self.parse)()?;
+
+        exports(input.as_ref())?
+            .iter()
+            .map(|(name, export)| {
+                export.save(dir, &format!("{}-{:0>2}-{name}", self.year, self.day))
+            })
+            .collect()
+    }
+
+    /// parse the input and solve both parts, timing each step
+    pub fn run(&self) -> eyre::Result<Report> {
+        self.run_variant(DEFAULT_VARIANT)
+    }
+
+    /// as `run`, with a particular implementation
+    pub fn run_variant(&self, name: &str) -> eyre::Result<Report> {
+        Ok(self.solve(name)?.1)
+    }
+
+    /// as `run_variant`, then play the day's frames on the same input
+    pub fn run_visualized(&self, name: &str, player: &Player) -> eyre::Result<Report> {
+        let (input, report) = self.solve(name)?;
+        self.play(input.as_ref(), player)?;
+        Ok(report)
+    }
+
+    fn solve(&self, name: &str) -> eyre::Result<(Input, Report)> {
+        let variant = self
+            .variant(name)
+            .ok_or_else(|| eyre::eyre!("{}/{:0>2} has no variant `{name}`", self.year, self.day))?;
+
+        let (input, parse_time) = timed(|| (self.parse)())?;
+        let (part_one, part_one_time) = timed(|| variant.part_one(input.as_ref()))?;
+        let (part_two, part_two_time) = timed(|| variant.part_two(input.as_ref()))?;
+
+        tracing::info!("Y{}D{:0>2}P01: {part_one}", self.year, self.day);
+        tracing::info!("Y{}D{:0>2}P02: {part_two}", self.year, self.day);
+
+        let report = Report {
+            year: self.year,
+            day: self.day,
+            variant: variant.name,
+            part_one,
+            part_two,
+            parse_time,
+            part_one_time,
+            part_two_time,
+        };
+
+        Ok((input, report))
+    }
+}
+
+fn timed<T>(f: impl FnOnce() -> eyre::Result<T>) -> eyre::Result<(T, Duration)> {
+    let start = Instant::now();
+    let result = f()?;
+    Ok((result, start.elapsed()))
+}
+
+/// the answers and timings from running a day
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub year: u64,
+    pub day: u64,
+    pub variant: &'static str,
+    pub part_one: String,
+    pub part_two: String,
+    pub parse_time: Duration,
+    pub part_one_time: Duration,
+    pub part_two_time: Duration,
+}
+
+impl Report {
+    pub fn total(&self) -> Duration {
+        self.parse_time + self.part_one_time + self.part_two_time
+    }
+
+    pub fn over_budget(&self, threshold: Duration) -> bool {
+        self.total() > threshold
+    }
+}
+
+/// answers can span multiple lines (e.g. an unrecognised CRT), keep the table tidy
+fn one_line(answer: &str) -> String {
+    match answer.trim().lines().count() > 1 {
+        true => "(multi-line)".into(),
+        false => answer.trim().into(),
+    }
+}
+
+/// render a table of every day's answers and timings, flagging any day that
+/// took longer than `threshold`
+pub fn summary_table(reports: &[Report], threshold: Duration) -> String {
+    let header = [
+        "day", "part one", "part two", "parse", "p1 time", "p2 time", "total", "budget",
+    ];
+
+    let mut rows = reports
+        .iter()
+        .map(|r| {
+            [
+                match r.variant {
+                    DEFAULT_VARIANT => format!("{}/{:0>2}", r.year, r.day),
+                    variant => format!("{}/{:0>2} ({variant})", r.year, r.day),
+                },
+                one_line(&r.part_one),
+                one_line(&r.part_two),
+                format!("{:.2?}", r.parse_time),
+                format!("{:.2?}", r.part_one_time),
+                format!("{:.2?}", r.part_two_time),
+                format!("{:.2?}", r.total()),
+                match r.over_budget(threshold) {
+                    true => format!("over {threshold:?}"),
+                    false => String::new(),
+                },
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let total = reports.iter().map(Report::total).sum::<Duration>();
+    rows.push([
+        "total".into(),
+        String::new(),
+        String::new(),
+        String::new(),
+        String::new(),
+        String::new(),
+        format!("{total:.2?}"),
+        String::new(),
+    ]);
+
+    // size each column to fit its widest cell
+    let widths = (0..header.len())
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .chain([header[i].len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect::<Vec<_>>();
+
+    let render = |cells: Vec<&str>| {
+        cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join(" | ")
+            .trim_end()
+            .to_owned()
+    };
+
+    let mut lines = vec![render(header.to_vec())];
+    lines.push(
+        widths
+            .iter()
+            .map(|w| "-".repeat(*w))
+            .collect::<Vec<_>>()
+            .join("-+-"),
+    );
+    for row in &rows {
+        lines.push(render(row.iter().map(String::as_str).collect()));
+    }
+
+    lines.join("\n")
+}
+
+/// the same summary as `summary_table`, as pretty-printed json
+pub fn summary_json(reports: &[Report], threshold: Duration) -> eyre::Result<String> {
+    let days = reports
+        .iter()
+        .map(|r| {
+            json!({
+                "year": r.year,
+                "day": r.day,
+                "variant": r.variant,
+                "part_one": r.part_one,
+                "part_two": r.part_two,
+                "parse_us": r.parse_time.as_micros() as u64,
+                "part_one_us": r.part_one_time.as_micros() as u64,
+                "part_two_us": r.part_two_time.as_micros() as u64,
+                "total_us": r.total().as_micros() as u64,
+                "over_budget": r.over_budget(threshold),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let total = reports.iter().map(Report::total).sum::<Duration>();
+
+    Ok(serde_json::to_string_pretty(&json!({
+        "threshold_us": threshold.as_micros() as u64,
+        "total_us": total.as_micros() as u64,
+        "days": days,
+    }))?)
+}
@@ -0,0 +1,278 @@
+use std::path::{Path, PathBuf};
+
+use crate::util::calendar;
+
+/// the starting point for a new day, mirrors the "solution" snippet
+const TEMPLATE: &str = r#"use crate::runner::Solution;
+
+pub fn get_input() -> eyre::Result<()> {
+    let input = crate::util::get_input({year}, {day})?;
+
+    todo!()
+}
+
+pub fn part_one(input: &()) -> u64 {
+    todo!()
+}
+
+pub fn part_two(input: &()) -> u64 {
+    todo!()
+}
+
+pub fn solution() -> Solution {
+    Solution::new(
+        {year},
+        {day},
+        get_input,
+        |i| Ok(part_one(i)),
+        |i| Ok(part_two(i)),
+    )
+}
+"#;
+
+/// an empty year, days are registered into it by `register_module`
+const YEAR: &str = r#"use crate::runner::Solution;
+
+pub fn solutions() -> Vec<Solution> {
+    vec![]
+}
+"#;
+
+fn render(template: &str, year: u64, day: u64) -> String {
+    template
+        .replace("{year}", &year.to_string())
+        .replace("{day}", &day.to_string())
+}
+
+/// tidy a new file with rustfmt, e.g. so a short `Solution::new` call fits
+/// on one line. It's only cosmetic, so a missing rustfmt is just a warning.
+fn format(path: &Path) {
+    let status = std::process::Command::new("rustfmt")
+        .args(["--edition", "2021"])
+        .arg(path)
+        .status();
+
+    match status {
+        Ok(status) if status.success() => {}
+        Ok(status) => tracing::warn!("rustfmt failed on {}: {status}", path.display()),
+        Err(e) => tracing::warn!("unable to run rustfmt on {}: {e}", path.display()),
+    }
+}
+
+/// insert a block of `text` after the last line starting with `prefix`,
+/// or before `fallback` if no line matches. Either way it's kept apart from
+/// its neighbours by a blank line.
+fn insert_block(content: &str, prefix: &str, fallback: &str, text: &str) -> eyre::Result<String> {
+    let (index, text) = match content.lines().rfind(|l| l.starts_with(prefix)) {
+        Some(line) => {
+            // SAFETY: the line was taken from content, rfind will locate it
+            let start = content.rfind(line).unwrap();
+            (start + line.len() + 1, format!("\n{text}\n"))
+        }
+        None => (
+            content
+                .find(fallback)
+                .ok_or_else(|| eyre::eyre!("unable to find `{fallback}` to insert before"))?,
+            format!("{text}\n\n"),
+        ),
+    };
+
+    let mut result = content.to_owned();
+    result.insert_str(index.min(content.len()), &text);
+    Ok(result)
+}
+
+/// write `content` to `path` if it changed, logging what happened
+fn update(path: &Path, before: &str, after: String) -> eyre::Result<()> {
+    match before == after {
+        true => tracing::info!("{} already up to date", path.display()),
+        false => {
+            std::fs::write(path, after)?;
+            tracing::info!("updated {}", path.display());
+        }
+    }
+
+    Ok(())
+}
+
+/// register the module and its call in `src/yYYYY/mod.rs`
+fn register_module(root: &Path, year: u64, day: u64) -> eyre::Result<()> {
+    let path = root.join(format!("src/y{year}/mod.rs"));
+    let before = std::fs::read_to_string(&path)?;
+    let mut after = before.clone();
+
+    let feature = format!("#[cfg(feature = \"y{year}d{day:0>2}\")]");
+
+    if !after.contains(&format!("pub mod d{day:0>2};")) {
+        after = insert_block(
+            &after,
+            "pub mod d",
+            "pub fn solutions",
+            &format!("{feature}\npub mod d{day:0>2};"),
+        )?;
+    }
+
+    if !after.contains(&format!("d{day:0>2}::solution()")) {
+        let entry = format!("        {feature}\n        d{day:0>2}::solution(),");
+
+        after = match after.contains("vec![]") {
+            // first day of the year
+            true => after.replace("vec![]", &format!("vec![\n{entry}\n    ]")),
+            false => {
+                let index = after
+                    .find("\n    ]\n")
+                    .ok_or_else(|| eyre::eyre!("unable to find the end of solutions"))?;
+                let mut result = after.clone();
+                result.insert_str(index, &format!("\n{entry}"));
+                result
+            }
+        };
+    }
+
+    update(&path, &before, after)
+}
+
+/// make sure the `yYYYYdNN` feature exists and is enabled by default
+fn register_feature(root: &Path, year: u64, day: u64) -> eyre::Result<()> {
+    let path = root.join("Cargo.toml");
+    let before = std::fs::read_to_string(&path)?;
+    let mut after = before.clone();
+
+    let name = format!("y{year}d{day:0>2}");
+
+    if !after.contains(&format!("\n{name} = ")) {
+        let (index, text) = match after.lines().rfind(|l| l.starts_with(&format!("y{year}d"))) {
+            // follow on from the rest of the year
+            Some(line) => (
+                // SAFETY: the line was taken from after, rfind will locate it
+                after.rfind(line).unwrap() + line.len() + 1,
+                format!("{name} = []\n"),
+            ),
+
+            // first day of the year, start a new group
+            None => (
+                after
+                    .find("\n[dependencies]")
+                    .ok_or_else(|| eyre::eyre!("Cargo.toml has no dependencies section"))?,
+                format!("\n{name} = []\n"),
+            ),
+        };
+        after.insert_str(index, &text);
+    }
+
+    if !after.contains(&format!("\"{name}\"")) {
+        let default = after
+            .find("default = [")
+            .ok_or_else(|| eyre::eyre!("Cargo.toml has no default features"))?;
+        let close = after[default..]
+            .find("\n]")
+            .ok_or_else(|| eyre::eyre!("Cargo.toml default features aren't closed"))?;
+        after.insert_str(default + close, &format!("\n    \"{name}\","));
+    }
+
+    update(&path, &before, after)
+}
+
+/// create `src/yYYYY` and register it in lib.rs if missing
+fn register_year(root: &Path, year: u64) -> eyre::Result<()> {
+    let module = root.join(format!("src/y{year}/mod.rs"));
+    if !module.exists() {
+        std::fs::create_dir_all(root.join(format!("src/y{year}")))?;
+        std::fs::write(&module, YEAR)?;
+        tracing::info!("created {}", module.display());
+    }
+
+    // add the module and its entrypoint to lib.rs
+    let path = root.join("src/lib.rs");
+    let before = std::fs::read_to_string(&path)?;
+    let mut after = before.clone();
+
+    if !after.contains(&format!("pub mod y{year};")) {
+        let index = match after.lines().rfind(|l| l.starts_with("pub mod ")) {
+            // SAFETY: the line was taken from after, rfind will locate it
+            Some(line) => after.rfind(line).unwrap() + line.len() + 1,
+            None => 0,
+        };
+        after.insert_str(index, &format!("pub mod y{year};\n"));
+    }
+
+    if !after.contains(&format!("y{year}::solutions)")) {
+        let years = after
+            .find("pub const YEARS")
+            .ok_or_else(|| eyre::eyre!("lib.rs has no YEARS"))?;
+        let close = after[years..]
+            .find("];")
+            .ok_or_else(|| eyre::eyre!("lib.rs YEARS isn't closed"))?;
+        after.insert_str(years + close, &format!(", ({year}, y{year}::solutions)"));
+    }
+
+    update(&path, &before, after)
+}
+
+/// a day can be scaffolded before it unlocks, but not for a year that
+/// doesn't exist yet
+fn check_day(year: u64, day: u64, now: u64) -> eyre::Result<()> {
+    eyre::ensure!(
+        calendar::known_years(now).contains(&year),
+        "{year} is not a valid AoC year."
+    );
+    eyre::ensure!(
+        (1..=calendar::days_in_season(year)).contains(&day),
+        "{day} is not a valid AoC day for {year}."
+    );
+
+    Ok(())
+}
+
+/// scaffold a new day: create the module from a template, register it with
+/// its year and cargo feature, then fetch the input. Each step can be re-run
+/// safely, but an existing module is never overwritten. Benches pick the day
+/// up from the registry.
+pub fn new_day(year: u64, day: u64) -> eyre::Result<()> {
+    check_day(year, day, calendar::now())?;
+
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+
+    // a new year needs its module and entrypoint first
+    register_year(&root, year)?;
+
+    // create the module, refusing to clobber an existing solution
+    let module = root.join(format!("src/y{year}/d{day:0>2}.rs"));
+    match module.exists() {
+        true => tracing::warn!("{} already exists, leaving it alone", module.display()),
+        false => {
+            std::fs::write(&module, render(TEMPLATE, year, day))?;
+            format(&module);
+            tracing::info!("created {}", module.display());
+        }
+    }
+
+    register_module(&root, year, day)?;
+    register_feature(&root, year, day)?;
+
+    // grab the input while we're here, not fatal if it isn't available yet
+    if let Err(e) = crate::util::get_input(year, day) {
+        tracing::warn!("unable to fetch input for {year}/{day:0>2}: {e}");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn days_need_a_known_year() {
+        let now = calendar::unlocks_at(2025, 1);
+
+        assert!(check_day(2025, 12, now).is_ok());
+        assert!(check_day(2025, 13, now).is_err());
+
+        // next year can be prepared for, but not the one after
+        assert!(check_day(2026, 1, now).is_ok());
+        let error = check_day(2099, 1, now).unwrap_err();
+        assert_eq!(error.to_string(), "2099 is not a valid AoC year.");
+        assert!(check_day(calendar::FIRST_YEAR - 1, 1, now).is_err());
+    }
+}
@@ -0,0 +1,191 @@
+use std::{
+    ops::RangeInclusive,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// the first ever AoC
+pub const FIRST_YEAR: u64 = 2015;
+
+/// puzzles unlock at midnight UTC-5, which is 05:00 UTC
+const UNLOCK_OFFSET: u64 = 5 * 60 * 60;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// seconds since the unix epoch
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// days since the unix epoch for a given date, see
+/// http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146097 + day_of_era - 719468
+}
+
+/// the year of a unix timestamp, see
+/// http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn year_from_timestamp(timestamp: u64) -> u64 {
+    let days = (timestamp / SECONDS_PER_DAY) as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+
+    // january and february belong to the next year in this calendar
+    (year_of_era + era * 400 + if month >= 10 { 1 } else { 0 }) as _
+}
+
+/// how many puzzles a season has, from 2025 onwards there are only twelve
+pub fn days_in_season(year: u64) -> u64 {
+    match year < 2025 {
+        true => 25,
+        false => 12,
+    }
+}
+
+/// unix timestamp at which a puzzle unlocks
+pub fn unlocks_at(year: u64, day: u64) -> u64 {
+    days_from_civil(year as _, 12, day as _) as u64 * SECONDS_PER_DAY + UNLOCK_OFFSET
+}
+
+/// the most recent year to have started as of `now`
+pub fn latest_year(now: u64) -> u64 {
+    let year = year_from_timestamp(now);
+
+    match now >= unlocks_at(year, 1) {
+        true => year,
+        false => year - 1,
+    }
+}
+
+/// render a number of seconds like `2d 3h 12m`, dropping leading zeros
+pub fn format_duration(seconds: u64) -> String {
+    // round up, "unlocks in 0m" isn't helpful
+    let minutes = seconds.div_ceil(60);
+    let (days, hours, minutes) = (minutes / 1440, minutes / 60 % 24, minutes % 60);
+
+    match (days, hours) {
+        (0, 0) => format!("{minutes}m"),
+        (0, _) => format!("{hours}h {minutes}m"),
+        (_, _) => format!("{days}d {hours}h {minutes}m"),
+    }
+}
+
+/// every year that has started as of `now`, plus the next one so it can be
+/// prepared for before it unlocks
+pub fn known_years(now: u64) -> RangeInclusive<u64> {
+    FIRST_YEAR..=latest_year(now) + 1
+}
+
+/// check a year/day is a puzzle that exists and has unlocked as of `now`
+pub fn validate(year: u64, day: u64, now: u64) -> eyre::Result<()> {
+    eyre::ensure!(
+        known_years(now).contains(&year),
+        "{year} is not a valid AoC year."
+    );
+
+    let days = days_in_season(year);
+    eyre::ensure!(
+        (1..=days).contains(&day),
+        "{day} is not a valid AoC day, {year} has {days} days."
+    );
+
+    let unlocks = unlocks_at(year, day);
+    eyre::ensure!(
+        now >= unlocks,
+        "{year}/{day:0>2} unlocks in {}",
+        format_duration(unlocks - now)
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// seconds since the epoch for a UTC date and time
+    fn utc(year: i64, month: i64, day: i64, hour: u64, minute: u64) -> u64 {
+        days_from_civil(year, month, day) as u64 * SECONDS_PER_DAY + hour * 3600 + minute * 60
+    }
+
+    #[test]
+    fn dates_match_known_timestamps() {
+        assert_eq!(utc(1970, 1, 1, 0, 0), 0);
+        assert_eq!(utc(2000, 3, 1, 0, 0), 951868800);
+        assert_eq!(utc(2025, 12, 1, 4, 59), 1764565140);
+        assert_eq!(utc(2026, 1, 15, 0, 0), 1768435200);
+
+        assert_eq!(year_from_timestamp(0), 1970);
+        assert_eq!(year_from_timestamp(utc(2000, 2, 29, 12, 0)), 2000);
+        assert_eq!(year_from_timestamp(utc(2025, 12, 31, 23, 59)), 2025);
+        assert_eq!(year_from_timestamp(utc(2026, 1, 1, 0, 0)), 2026);
+    }
+
+    #[test]
+    fn puzzles_unlock_at_five_utc() {
+        assert_eq!(unlocks_at(2025, 1), utc(2025, 12, 1, 5, 0));
+
+        let error = validate(2025, 1, utc(2025, 12, 1, 4, 59)).unwrap_err();
+        assert_eq!(error.to_string(), "2025/01 unlocks in 1m");
+        assert!(validate(2025, 1, utc(2025, 12, 1, 5, 0)).is_ok());
+
+        let error = validate(2025, 3, utc(2025, 12, 1, 5, 0)).unwrap_err();
+        assert_eq!(error.to_string(), "2025/03 unlocks in 2d 0h 0m");
+    }
+
+    #[test]
+    fn seasons_shrink_from_2025() {
+        let later = utc(2026, 6, 1, 0, 0);
+        assert!(validate(2024, 25, later).is_ok());
+        assert!(validate(2025, 12, later).is_ok());
+        assert!(validate(2025, 13, later).is_err());
+        assert!(validate(2025, 0, later).is_err());
+    }
+
+    #[test]
+    fn next_year_is_known_but_locked() {
+        let now = utc(2025, 12, 10, 0, 0);
+        assert_eq!(latest_year(now), 2025);
+
+        // 2026 exists, it just hasn't unlocked yet
+        let error = validate(2026, 1, now).unwrap_err();
+        assert!(
+            error.to_string().starts_with("2026/01 unlocks in"),
+            "{error}"
+        );
+        assert!(validate(2026, 1, utc(2026, 12, 1, 5, 0)).is_ok());
+
+        let error = validate(2027, 1, now).unwrap_err();
+        assert_eq!(error.to_string(), "2027 is not a valid AoC year.");
+        assert!(validate(FIRST_YEAR - 1, 1, now).is_err());
+        assert_eq!(known_years(now), FIRST_YEAR..=2026);
+    }
+
+    #[test]
+    fn latest_year_waits_for_december() {
+        assert_eq!(latest_year(utc(2026, 1, 15, 0, 0)), 2025);
+        assert_eq!(latest_year(utc(2026, 12, 1, 4, 59)), 2025);
+        assert_eq!(latest_year(utc(2026, 12, 1, 5, 0)), 2026);
+    }
+
+    #[test]
+    fn durations_drop_leading_zeros() {
+        assert_eq!(format_duration(1), "1m");
+        assert_eq!(format_duration(60), "1m");
+        assert_eq!(format_duration(61), "2m");
+        assert_eq!(format_duration(3600), "1h 0m");
+        assert_eq!(format_duration(SECONDS_PER_DAY + 90), "1d 0h 2m");
+    }
+}
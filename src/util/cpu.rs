@@ -0,0 +1,296 @@
+use std::{collections::HashSet, fmt::Debug};
+
+use eyre::Context;
+
+/// the registers and counters of a running program
+#[derive(Debug, Clone, Default)]
+pub struct State {
+    // index of the next instruction to fetch
+    pub program_counter: usize,
+
+    // cycles elapsed, including the current one
+    pub cycle: u64,
+
+    pub registers: Vec<i64>,
+}
+
+/// a single instruction, taking some number of cycles before it's applied
+pub trait Instruction: Debug {
+    /// how many cycles the instruction is busy for, at least one
+    fn cycles(&self) -> u64;
+
+    /// apply the instruction once its cycles have elapsed. The program counter
+    /// has already moved past it, so jumps can overwrite it.
+    fn execute(&self, state: &mut State) -> eyre::Result<()>;
+}
+
+/// something watching the cpu, called during every cycle
+pub trait Observer {
+    fn on_cycle(&mut self, state: &State);
+}
+
+impl<F: FnMut(&State)> Observer for F {
+    fn on_cycle(&mut self, state: &State) {
+        self(state)
+    }
+}
+
+/// why a run came to an end
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    // ran off the end of the program
+    Halted,
+
+    // about to fetch the instruction at this address
+    Breakpoint(usize),
+
+    // the `run_until` condition was met
+    Condition,
+}
+
+/// a register snapshot taken during a cycle
+#[derive(Debug, Clone)]
+pub struct TraceEntry {
+    pub cycle: u64,
+    pub program_counter: usize,
+    pub instruction: String,
+    pub registers: Vec<i64>,
+}
+
+#[derive(Debug)]
+pub struct Cpu<I: Instruction> {
+    pub program: Vec<I>,
+    pub state: State,
+
+    // register names, only used when dumping the trace
+    pub names: Vec<&'static str>,
+
+    // the instruction being worked on, and how many cycles it has left
+    pending: Option<(usize, u64)>,
+
+    breakpoints: HashSet<usize>,
+
+    // the breakpoint the last run stopped at, passed over when resuming
+    stopped_at: Option<usize>,
+    trace: Option<Vec<TraceEntry>>,
+}
+
+impl<I: Instruction> Cpu<I> {
+    /// load a program, with a zeroed register for each name
+    pub fn new(program: Vec<I>, names: &[&'static str]) -> Self {
+        Self {
+            program,
+            state: State {
+                registers: vec![0; names.len()],
+                ..Default::default()
+            },
+            names: names.to_vec(),
+            pending: None,
+            breakpoints: HashSet::new(),
+            stopped_at: None,
+            trace: None,
+        }
+    }
+
+    pub fn add_breakpoint(&mut self, address: usize) -> &mut Self {
+        self.breakpoints.insert(address);
+        self
+    }
+
+    pub fn remove_breakpoint(&mut self, address: usize) -> &mut Self {
+        self.breakpoints.remove(&address);
+        self
+    }
+
+    /// start recording every cycle, see `dump_trace`
+    pub fn enable_trace(&mut self) -> &mut Self {
+        self.trace.get_or_insert_with(Vec::new);
+        self
+    }
+
+    pub fn trace(&self) -> &[TraceEntry] {
+        self.trace.as_deref().unwrap_or_default()
+    }
+
+    /// has the program run off the end, with nothing left in flight
+    pub fn halted(&self) -> bool {
+        self.pending.is_none() && self.state.program_counter >= self.program.len()
+    }
+
+    /// advance a single cycle, returning false if the program has halted
+    pub fn step(&mut self, observer: &mut impl Observer) -> eyre::Result<bool> {
+        // once moving, a breakpoint that was stopped at counts again
+        self.stopped_at = None;
+
+        // fetch the next instruction if the last one is done
+        let (address, remaining) = match self.pending {
+            Some(pending) => pending,
+            None => match self.program.get(self.state.program_counter) {
+                Some(instruction) => (self.state.program_counter, instruction.cycles().max(1)),
+                None => return Ok(false),
+            },
+        };
+
+        self.state.cycle += 1;
+        observer.on_cycle(&self.state);
+
+        if let Some(trace) = &mut self.trace {
+            trace.push(TraceEntry {
+                cycle: self.state.cycle,
+                program_counter: address,
+                instruction: format!("{:?}", self.program[address]),
+                registers: self.state.registers.clone(),
+            });
+        }
+
+        // the instruction only lands at the end of its last cycle
+        match remaining - 1 {
+            0 => {
+                self.pending = None;
+                self.state.program_counter = address + 1;
+                let cycle = self.state.cycle;
+                self.program[address]
+                    .execute(&mut self.state)
+                    .wrap_err_with(|| format!("cycle {cycle}, address {address}"))?;
+            }
+            remaining => self.pending = Some((address, remaining)),
+        }
+
+        Ok(true)
+    }
+
+    /// run until the program halts, a breakpoint is hit or `condition` holds
+    /// at the end of a cycle. Only the breakpoint the last run stopped at is
+    /// passed over, so a stopped cpu can be resumed.
+    pub fn run_until(
+        &mut self,
+        observer: &mut impl Observer,
+        mut condition: impl FnMut(&State) -> bool,
+    ) -> eyre::Result<Stop> {
+        loop {
+            let address = self.state.program_counter;
+            if self.pending.is_none()
+                && self.breakpoints.contains(&address)
+                && self.stopped_at != Some(address)
+            {
+                self.stopped_at = Some(address);
+                return Ok(Stop::Breakpoint(address));
+            }
+
+            if !self.step(observer)? {
+                return Ok(Stop::Halted);
+            }
+
+            if condition(&self.state) {
+                return Ok(Stop::Condition);
+            }
+        }
+    }
+
+    /// run until the program halts or a breakpoint is hit
+    pub fn run(&mut self, observer: &mut impl Observer) -> eyre::Result<Stop> {
+        self.run_until(observer, |_| false)
+    }
+
+    /// render the trace, one cycle per line
+    pub fn dump_trace(&self) -> String {
+        self.trace()
+            .iter()
+            .map(|entry| {
+                let registers = self
+                    .names
+                    .iter()
+                    .zip(&entry.registers)
+                    .map(|(name, value)| format!("{name}={value}"))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+
+                format!(
+                    "{:>6} | {:>4} | {:<16} | {registers}",
+                    entry.cycle, entry.program_counter, entry.instruction
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// add one to the only register, taking two cycles
+    #[derive(Debug)]
+    struct Inc;
+
+    impl Instruction for Inc {
+        fn cycles(&self) -> u64 {
+            2
+        }
+
+        fn execute(&self, state: &mut State) -> eyre::Result<()> {
+            state.registers[0] += 1;
+            Ok(())
+        }
+    }
+
+    /// fails as soon as it lands
+    #[derive(Debug)]
+    struct Fault;
+
+    impl Instruction for Fault {
+        fn cycles(&self) -> u64 {
+            3
+        }
+
+        fn execute(&self, _: &mut State) -> eyre::Result<()> {
+            Err(eyre::eyre!("no such luck")).wrap_err("faulted")
+        }
+    }
+
+    #[test]
+    fn errors_keep_their_chain() {
+        let mut cpu = Cpu::new(vec![Fault], &["a"]);
+        let error = cpu.run(&mut |_: &State| {}).unwrap_err();
+
+        assert_eq!(error.to_string(), "cycle 3, address 0");
+        assert_eq!(
+            error.chain().map(ToString::to_string).collect::<Vec<_>>(),
+            ["cycle 3, address 0", "faulted", "no such luck"]
+        );
+    }
+
+    fn cpu() -> Cpu<Inc> {
+        Cpu::new((0..3).map(|_| Inc).collect(), &["a"])
+    }
+
+    #[test]
+    fn breaks_on_the_first_instruction() {
+        let mut cpu = cpu();
+        cpu.add_breakpoint(0).add_breakpoint(2);
+
+        assert_eq!(cpu.run(&mut |_: &State| {}).unwrap(), Stop::Breakpoint(0));
+        assert_eq!(cpu.state.cycle, 0);
+
+        // resuming passes over the breakpoint it stopped at, not the next
+        assert_eq!(cpu.run(&mut |_: &State| {}).unwrap(), Stop::Breakpoint(2));
+        assert_eq!(cpu.state.registers, [2]);
+
+        assert_eq!(cpu.run(&mut |_: &State| {}).unwrap(), Stop::Halted);
+        assert_eq!(cpu.state.registers, [3]);
+    }
+
+    #[test]
+    fn breaks_after_a_condition() {
+        let mut cpu = cpu();
+        cpu.add_breakpoint(1);
+
+        // stops between instructions, right where the breakpoint is
+        let stop = cpu.run_until(&mut |_: &State| {}, |s| s.cycle == 2);
+        assert_eq!(stop.unwrap(), Stop::Condition);
+        assert_eq!(cpu.state.program_counter, 1);
+
+        assert_eq!(cpu.run(&mut |_: &State| {}).unwrap(), Stop::Breakpoint(1));
+        assert_eq!(cpu.state.cycle, 2);
+    }
+}
@@ -0,0 +1,274 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    ops::{Add, Mul, Sub},
+};
+
+/// where a sequence of states starts repeating, and how often
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    // index of the first state that is part of the loop
+    pub start: usize,
+
+    // number of steps taken to come back around
+    pub length: usize,
+}
+
+impl Cycle {
+    /// map step `n` onto the equivalent step before the loop first completes
+    pub fn index_of(&self, n: usize) -> usize {
+        match n < self.start {
+            true => n,
+            false => self.start + (n - self.start) % self.length,
+        }
+    }
+
+    /// extrapolate an accumulated value (height, score, inspections...) to
+    /// step `n`. `history[i]` must hold the value after `i` steps, covering at
+    /// least one full loop (`start + length` entries, plus one).
+    pub fn extrapolate<T>(&self, history: &[T], n: usize) -> Option<T>
+    where
+        T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + TryFrom<usize>,
+    {
+        if n < history.len() {
+            return history.get(n).copied();
+        }
+
+        // how much is gained per trip around the loop
+        let first = *history.get(self.start)?;
+        let gain = *history.get(self.start + self.length)? - first;
+
+        let loops = T::try_from((n - self.start) / self.length).ok()?;
+        let remainder = *history.get(self.index_of(n))? - first;
+
+        Some(first + loops * gain + remainder)
+    }
+}
+
+/// Floyd's tortoise and hare. Only stores two states at a time but steps the
+/// sequence roughly three times over. The sequence must eventually repeat.
+pub fn floyd<S, K, F, P>(initial: S, mut step: F, key: P) -> Cycle
+where
+    S: Clone,
+    K: PartialEq,
+    F: FnMut(&S) -> S,
+    P: Fn(&S) -> K,
+{
+    // find a meeting point somewhere inside the loop
+    let mut tortoise = step(&initial);
+    let mut hare = step(&tortoise);
+    while key(&tortoise) != key(&hare) {
+        tortoise = step(&tortoise);
+        let next = step(&hare);
+        hare = step(&next);
+    }
+
+    // walk from the beginning to find where the loop starts
+    let mut start = 0;
+    tortoise = initial;
+    while key(&tortoise) != key(&hare) {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    // and once more around to measure it
+    let mut length = 1;
+    hare = step(&tortoise);
+    while key(&tortoise) != key(&hare) {
+        hare = step(&hare);
+        length += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// Brent's algorithm. Same guarantees as `floyd`, usually fewer steps.
+pub fn brent<S, K, F, P>(initial: S, mut step: F, key: P) -> Cycle
+where
+    S: Clone,
+    K: PartialEq,
+    F: FnMut(&S) -> S,
+    P: Fn(&S) -> K,
+{
+    // find the loop length by teleporting the tortoise at powers of two
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while key(&tortoise) != key(&hare) {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    // put the hare `length` steps ahead, then advance both until they meet
+    let mut start = 0;
+    tortoise = initial.clone();
+    hare = initial;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    while key(&tortoise) != key(&hare) {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// remember every key seen, stopping at the first repeat. Uses more memory
+/// than `floyd`/`brent` but only steps the sequence once, and gives up after
+/// `limit` steps rather than spinning forever on a sequence that never loops.
+pub fn detect<S, K, F, P>(initial: S, limit: usize, mut step: F, key: P) -> Option<Cycle>
+where
+    K: Hash + Eq,
+    F: FnMut(&S) -> S,
+    P: Fn(&S) -> K,
+{
+    let mut seen = HashMap::new();
+    let mut state = initial;
+
+    for index in 0..=limit {
+        if let Some(start) = seen.insert(key(&state), index) {
+            return Some(Cycle {
+                start,
+                length: index - start,
+            });
+        }
+
+        // don't step past the limit, the state after it may not exist
+        if index == limit {
+            break;
+        }
+
+        state = step(&state);
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0, 1, .. 7 then back to 3, a tail of three into a loop of five
+    fn rho(x: &usize) -> usize {
+        match *x < 7 {
+            true => x + 1,
+            false => 3,
+        }
+    }
+
+    fn squares(x: &u64) -> u64 {
+        (x * x + 1) % 1009
+    }
+
+    #[test]
+    fn detectors_agree_on_a_known_loop() {
+        let expected = Cycle {
+            start: 3,
+            length: 5,
+        };
+
+        assert_eq!(floyd(0, rho, |x| *x), expected);
+        assert_eq!(brent(0, rho, |x| *x), expected);
+        assert_eq!(detect(0, 100, rho, |x| *x), Some(expected));
+    }
+
+    #[test]
+    fn detectors_agree_with_each_other() {
+        for seed in 0..200 {
+            let found = detect(seed, 2000, squares, |x| *x).unwrap();
+            assert_eq!(floyd(seed, squares, |x| *x), found, "seed {seed}");
+            assert_eq!(brent(seed, squares, |x| *x), found, "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn detect_gives_up_at_the_limit() {
+        assert_eq!(detect(0, 7, rho, |x| *x), None);
+        assert!(detect(0, 8, rho, |x| *x).is_some());
+        assert_eq!(detect(0_u64, 1000, |x| x + 1, |x| *x), None);
+    }
+
+    #[test]
+    fn detect_never_steps_past_the_limit() {
+        let mut steps = 0;
+        let found = detect(
+            0_u64,
+            5,
+            |x| {
+                steps += 1;
+                assert!(*x < 5, "stepped past the limit");
+                x + 1
+            },
+            |x| *x,
+        );
+
+        assert_eq!(found, None);
+        assert_eq!(steps, 5);
+    }
+
+    #[test]
+    fn keys_project_the_state() {
+        // only the value mod 4 matters, so it loops straight away
+        let found = brent((0_u64, 0_u64), |(x, n)| ((x + 1) % 4, n + 1), |s| s.0);
+        assert_eq!(
+            found,
+            Cycle {
+                start: 0,
+                length: 4
+            }
+        );
+    }
+
+    #[test]
+    fn index_of_wraps_into_the_loop() {
+        let cycle = Cycle {
+            start: 3,
+            length: 5,
+        };
+
+        assert_eq!(cycle.index_of(2), 2);
+        assert_eq!(cycle.index_of(3), 3);
+        assert_eq!(cycle.index_of(8), 3);
+        assert_eq!(cycle.index_of(14), 4);
+    }
+
+    #[test]
+    fn extrapolate_matches_brute_force() {
+        // a running total of the states
+        let totals = |n: usize| {
+            let mut x = 0;
+            let mut total = vec![0_u64];
+            for _ in 0..n {
+                x = rho(&x);
+                total.push(total.last().unwrap() + x as u64);
+            }
+            total
+        };
+
+        let found = floyd(0, rho, |x| *x);
+        let history = totals(found.start + found.length);
+        let expected = totals(500);
+
+        for (n, total) in expected.into_iter().enumerate() {
+            assert_eq!(found.extrapolate(&history, n), Some(total), "step {n}");
+        }
+    }
+
+    #[test]
+    fn extrapolate_needs_a_full_loop() {
+        let found = Cycle {
+            start: 3,
+            length: 5,
+        };
+        assert_eq!(found.extrapolate(&[0_u64, 1, 2, 3], 100), None);
+    }
+}
@@ -0,0 +1,299 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+pub type Rgb = [u8; 3];
+
+/// how a cell is drawn when exported as an image
+pub trait Colour {
+    fn colour(&self) -> Rgb;
+}
+
+impl Colour for bool {
+    fn colour(&self) -> Rgb {
+        match self {
+            true => [255, 255, 255],
+            false => [16, 16, 16],
+        }
+    }
+}
+
+impl Colour for Rgb {
+    fn colour(&self) -> Rgb {
+        *self
+    }
+}
+
+/// blend between two colours, `t` runs from 0 to 1
+pub fn gradient(from: Rgb, to: Rgb, t: f64) -> Rgb {
+    let t = t.clamp(0.0, 1.0);
+    [0, 1, 2].map(|i| (from[i] as f64 + (to[i] as f64 - from[i] as f64) * t).round() as u8)
+}
+
+/// an rgb image, pixels stored row by row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<Rgb>,
+}
+
+impl Image {
+    /// an image of rows of cells, every row must be the same length
+    pub fn from_rows<T: Colour>(
+        rows: impl IntoIterator<Item = impl IntoIterator<Item = T>>,
+    ) -> eyre::Result<Self> {
+        let mut width = None;
+        let mut height = 0;
+        let mut pixels = Vec::new();
+
+        for row in rows {
+            let before = pixels.len();
+            pixels.extend(row.into_iter().map(|cell| cell.colour()));
+
+            let length = pixels.len() - before;
+            match width {
+                None => width = Some(length),
+                Some(w) => eyre::ensure!(
+                    w == length,
+                    "row {height} is {length} cells wide, expected {w}"
+                ),
+            }
+            height += 1;
+        }
+
+        Ok(Self {
+            width: width.unwrap_or(0),
+            height,
+            pixels,
+        })
+    }
+
+    /// an image of something's text rendering, a cell per character. Short
+    /// lines are padded with `palette(' ')`.
+    pub fn from_text(text: &str, palette: impl Fn(char) -> Rgb) -> Self {
+        let lines = text.lines().collect::<Vec<_>>();
+        let width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+
+        Self {
+            width,
+            height: lines.len(),
+            pixels: lines
+                .iter()
+                .flat_map(|l| l.chars().chain(std::iter::repeat(' ')).take(width))
+                .map(palette)
+                .collect(),
+        }
+    }
+
+    /// blow each pixel up into a `factor` square, small grids are hard to see
+    pub fn scale(&self, factor: usize) -> Self {
+        let factor = factor.max(1);
+
+        Self {
+            width: self.width * factor,
+            height: self.height * factor,
+            pixels: self
+                .pixels
+                .chunks(self.width.max(1))
+                .flat_map(|row| {
+                    let row = row
+                        .iter()
+                        .flat_map(|p| std::iter::repeat_n(*p, factor))
+                        .collect::<Vec<_>>();
+                    std::iter::repeat_n(row, factor).flatten()
+                })
+                .collect(),
+        }
+    }
+
+    /// binary PPM (P6)
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut out = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        out.extend(self.pixels.iter().flatten());
+        out
+    }
+
+    pub fn to_png(&self) -> eyre::Result<Vec<u8>> {
+        let mut out = Vec::new();
+
+        let mut encoder = png::Encoder::new(&mut out, self.width as _, self.height as _);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels.concat())?;
+        writer.finish()?;
+
+        Ok(out)
+    }
+
+    /// write a `.ppm` or `.png`, picked by the path's extension
+    pub fn save(&self, path: impl AsRef<Path>) -> eyre::Result<()> {
+        let path = path.as_ref();
+        let bytes = match path.extension().and_then(|e| e.to_str()) {
+            Some("ppm") => self.to_ppm(),
+            Some("png") => self.to_png()?,
+            _ => eyre::bail!("{} should end in .ppm or .png", path.display()),
+        };
+
+        Ok(std::fs::write(path, bytes)?)
+    }
+}
+
+/// an animated gif that loops forever, each frame shown for `delay`
+/// hundredths of a second. The palette is built from the colours in the
+/// order they first appear, so the same frames always give the same bytes.
+pub fn to_gif(frames: &[Image], delay: u16) -> eyre::Result<Vec<u8>> {
+    let first = frames
+        .first()
+        .ok_or_else(|| eyre::eyre!("a gif needs at least one frame"))?;
+    let (width, height) = (first.width, first.height);
+    eyre::ensure!(
+        width <= u16::MAX as usize && height <= u16::MAX as usize,
+        "{width}x{height} is too big for a gif"
+    );
+
+    let mut palette = HashMap::new();
+    let mut colours = Vec::new();
+    let mut indexed = Vec::new();
+
+    for (index, frame) in frames.iter().enumerate() {
+        eyre::ensure!(
+            (frame.width, frame.height) == (width, height),
+            "frame {index} is {}x{}, expected {width}x{height}",
+            frame.width,
+            frame.height
+        );
+
+        let mut pixels = Vec::with_capacity(frame.pixels.len());
+        for pixel in &frame.pixels {
+            let entry = match palette.get(pixel) {
+                Some(entry) => *entry,
+                None => {
+                    eyre::ensure!(colours.len() < 256, "a gif can only hold 256 colours");
+                    let entry = colours.len() as u8;
+                    palette.insert(*pixel, entry);
+                    colours.push(*pixel);
+                    entry
+                }
+            };
+            pixels.push(entry);
+        }
+        indexed.push(pixels);
+    }
+
+    let mut out = Vec::new();
+    {
+        let mut encoder = gif::Encoder::new(&mut out, width as _, height as _, &colours.concat())?;
+        encoder.set_repeat(gif::Repeat::Infinite)?;
+
+        for pixels in indexed {
+            let mut frame = gif::Frame::from_indexed_pixels(width as _, height as _, pixels, None);
+            frame.delay = delay;
+            encoder.write_frame(&frame)?;
+        }
+    }
+
+    Ok(out)
+}
+
+/// write frames out as an animated gif, see `to_gif`
+pub fn save_gif(frames: &[Image], delay: u16, path: impl AsRef<Path>) -> eyre::Result<()> {
+    Ok(std::fs::write(path, to_gif(frames, delay)?)?)
+}
+
+/// a day's exports, each with a name to save it under
+pub type Exports = Vec<(&'static str, Export)>;
+
+/// something a day can write out, see `Solution::with_export`
+#[derive(Debug, Clone)]
+pub enum Export {
+    Still(Image),
+
+    // frames, and how long each is shown in hundredths of a second
+    Animated(Vec<Image>, u16),
+
+    // anything else worth keeping, e.g. csv or json, and its extension
+    Text(String, &'static str),
+}
+
+impl Export {
+    /// write `name.png`, `name.gif` or the text's extension into `dir`,
+    /// returning the path
+    pub fn save(&self, dir: impl AsRef<Path>, name: &str) -> eyre::Result<PathBuf> {
+        let path = dir.as_ref().join(name);
+        let path = match self {
+            Export::Still(image) => {
+                let path = path.with_extension("png");
+                image.save(&path)?;
+                path
+            }
+            Export::Animated(frames, delay) => {
+                let path = path.with_extension("gif");
+                save_gif(frames, *delay, &path)?;
+                path
+            }
+            Export::Text(text, extension) => {
+                let path = path.with_extension(extension);
+                std::fs::write(&path, text)?;
+                path
+            }
+        };
+
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a tiny grid with every channel on its own, checked against files in
+    // `testdata` so any change to the encoded bytes shows up
+    fn grid() -> Image {
+        Image::from_rows([
+            [[255, 0, 0], [0, 255, 0], [0, 0, 255]],
+            [[0, 0, 0], [255, 255, 255], [255, 0, 0]],
+        ])
+        .unwrap()
+    }
+
+    fn flipped() -> Image {
+        let mut image = grid();
+        image.pixels.rotate_left(3);
+        image
+    }
+
+    #[test]
+    fn ppm_matches_golden() {
+        assert_eq!(grid().to_ppm(), include_bytes!("testdata/grid.ppm"));
+    }
+
+    #[test]
+    fn png_matches_golden() {
+        assert_eq!(
+            grid().to_png().unwrap(),
+            include_bytes!("testdata/grid.png")
+        );
+    }
+
+    #[test]
+    fn gif_matches_golden() {
+        let gif = to_gif(&[grid(), flipped()], 10).unwrap();
+        assert_eq!(gif, include_bytes!("testdata/grid.gif"));
+    }
+
+    #[test]
+    fn rows_must_be_the_same_width() {
+        let rows = vec![vec![true, false], vec![true]];
+        assert!(Image::from_rows(rows).is_err());
+    }
+
+    #[test]
+    fn frames_must_be_the_same_size() {
+        assert!(to_gif(&[grid(), grid().scale(2)], 10).is_err());
+        assert!(to_gif(&[], 10).is_err());
+    }
+}
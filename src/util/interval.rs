@@ -0,0 +1,296 @@
+use std::{fmt::Display, ops::RangeInclusive};
+
+/// an inclusive range of integers, `start..=end`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval {
+    pub start: i64,
+    pub end: i64,
+}
+
+impl Interval {
+    /// build an interval, swapping the bounds if they're given backwards
+    pub fn new(start: i64, end: i64) -> Self {
+        Self {
+            start: start.min(end),
+            end: start.max(end),
+        }
+    }
+
+    /// how many integers are covered. The whole of `i64` is one more than
+    /// fits in a `u64`, so that saturates at `u64::MAX`.
+    pub fn count(&self) -> u64 {
+        self.start.abs_diff(self.end).saturating_add(1)
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        self.start <= value && value <= self.end
+    }
+
+    /// does this interval fully contain `other`?
+    pub fn contains_interval(&self, other: &Self) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    /// do the intervals share at least one integer?
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    /// do the intervals overlap, or sit directly next to each other?
+    pub fn touches(&self, other: &Self) -> bool {
+        self.start <= other.end.saturating_add(1) && other.start <= self.end.saturating_add(1)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        self.overlaps(other).then(|| Self {
+            start: self.start.max(other.start),
+            end: self.end.min(other.end),
+        })
+    }
+
+    /// remove `other` from this interval, leaving up to two pieces
+    pub fn difference(&self, other: &Self) -> Vec<Self> {
+        if !self.overlaps(other) {
+            return vec![*self];
+        }
+
+        let mut result = Vec::new();
+        if self.start < other.start {
+            result.push(Self::new(self.start, other.start - 1));
+        }
+        if other.end < self.end {
+            result.push(Self::new(other.end + 1, self.end));
+        }
+
+        result
+    }
+
+    pub fn iter(&self) -> RangeInclusive<i64> {
+        self.start..=self.end
+    }
+}
+
+impl From<RangeInclusive<i64>> for Interval {
+    fn from(range: RangeInclusive<i64>) -> Self {
+        Self::new(*range.start(), *range.end())
+    }
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}..={}", self.start, self.end)
+    }
+}
+
+/// a set of integers, stored as sorted, disjoint and non-adjacent intervals
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RangeSet {
+    intervals: Vec<Interval>,
+}
+
+impl RangeSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// add an interval, merging it with any it overlaps or touches
+    pub fn insert(&mut self, interval: Interval) -> &mut Self {
+        let mut merged = interval;
+
+        // everything before the first touching interval stays put
+        let from = self
+            .intervals
+            .partition_point(|i| i.end.saturating_add(1) < interval.start);
+
+        // absorb every interval that touches the new one
+        let mut to = from;
+        while to < self.intervals.len() && self.intervals[to].touches(&merged) {
+            merged = Interval::new(
+                merged.start.min(self.intervals[to].start),
+                merged.end.max(self.intervals[to].end),
+            );
+            to += 1;
+        }
+
+        self.intervals.splice(from..to, [merged]);
+        self
+    }
+
+    /// remove every integer in `interval` from the set
+    pub fn remove(&mut self, interval: Interval) -> &mut Self {
+        self.intervals = self
+            .intervals
+            .iter()
+            .flat_map(|i| i.difference(&interval))
+            .collect();
+        self
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        other.iter().for_each(|i| {
+            result.insert(*i);
+        });
+        result
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        other.iter().for_each(|i| {
+            result.remove(*i);
+        });
+        result
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut result = Self::new();
+        for a in self.iter() {
+            for b in other.iter().filter_map(|b| a.intersection(b)) {
+                result.insert(b);
+            }
+        }
+        result
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        let index = self.intervals.partition_point(|i| i.end < value);
+        self.intervals
+            .get(index)
+            .map(|i| i.contains(value))
+            .unwrap_or(false)
+    }
+
+    /// how many integers are covered by the set, saturating like `count`
+    pub fn len(&self) -> u64 {
+        self.intervals
+            .iter()
+            .fold(0, |total: u64, i| total.saturating_add(i.count()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// the uncovered intervals between `bounds.start` and `bounds.end`
+    pub fn gaps(&self, bounds: Interval) -> Self {
+        let mut result = Self::new();
+        result.insert(bounds);
+        result.difference(self)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Interval> {
+        self.intervals.iter()
+    }
+}
+
+impl FromIterator<Interval> for RangeSet {
+    fn from_iter<T: IntoIterator<Item = Interval>>(iter: T) -> Self {
+        let mut result = Self::new();
+        iter.into_iter().for_each(|i| {
+            result.insert(i);
+        });
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(intervals: &[(i64, i64)]) -> RangeSet {
+        intervals
+            .iter()
+            .map(|(a, b)| Interval::new(*a, *b))
+            .collect()
+    }
+
+    fn pairs(set: &RangeSet) -> Vec<(i64, i64)> {
+        set.iter().map(|i| (i.start, i.end)).collect()
+    }
+
+    #[test]
+    fn intervals_count_and_compare() {
+        let a = Interval::new(5, 2);
+        assert_eq!((a.start, a.end), (2, 5));
+        assert_eq!(a.count(), 4);
+        assert_eq!(Interval::new(i64::MIN, i64::MAX).count(), u64::MAX);
+        assert_eq!(Interval::new(i64::MIN, -1).count(), 1 << 63);
+
+        assert!(a.contains_interval(&Interval::new(3, 4)));
+        assert!(a.overlaps(&Interval::new(5, 9)));
+        assert!(!a.overlaps(&Interval::new(6, 9)));
+        assert!(a.touches(&Interval::new(6, 9)));
+        assert!(!a.touches(&Interval::new(7, 9)));
+        assert_eq!(
+            a.intersection(&Interval::new(4, 9)),
+            Some(Interval::new(4, 5))
+        );
+        assert_eq!(a.intersection(&Interval::new(6, 9)), None);
+        assert_eq!(
+            Interval::new(0, 10).difference(&Interval::new(3, 4)),
+            [Interval::new(0, 2), Interval::new(5, 10)]
+        );
+    }
+
+    #[test]
+    fn insert_merges_overlapping_and_adjacent() {
+        let mut ranges = set(&[(10, 12), (0, 2), (20, 25)]);
+        assert_eq!(pairs(&ranges), [(0, 2), (10, 12), (20, 25)]);
+
+        // adjacent, so it joins up
+        ranges.insert(Interval::new(3, 4));
+        assert_eq!(pairs(&ranges), [(0, 4), (10, 12), (20, 25)]);
+
+        // spans several, swallowing them all
+        ranges.insert(Interval::new(11, 21));
+        assert_eq!(pairs(&ranges), [(0, 4), (10, 25)]);
+
+        // a gap of one stays a gap
+        ranges.insert(Interval::new(6, 8));
+        assert_eq!(pairs(&ranges), [(0, 4), (6, 8), (10, 25)]);
+        assert_eq!(ranges.len(), 5 + 3 + 16);
+
+        ranges.insert(Interval::new(i64::MIN, i64::MAX));
+        assert_eq!(pairs(&ranges), [(i64::MIN, i64::MAX)]);
+        assert_eq!(ranges.len(), u64::MAX);
+    }
+
+    #[test]
+    fn remove_splits_intervals() {
+        let mut ranges = set(&[(0, 10), (20, 30)]);
+        ranges.remove(Interval::new(5, 25));
+        assert_eq!(pairs(&ranges), [(0, 4), (26, 30)]);
+
+        ranges.remove(Interval::new(2, 2));
+        assert_eq!(pairs(&ranges), [(0, 1), (3, 4), (26, 30)]);
+        assert!(!ranges.contains(2));
+        assert!(ranges.contains(3));
+
+        ranges.remove(Interval::new(-100, 100));
+        assert!(ranges.is_empty());
+        assert_eq!(ranges.len(), 0);
+    }
+
+    #[test]
+    fn gaps_fill_the_bounds() {
+        let ranges = set(&[(2, 4), (8, 9)]);
+        assert_eq!(
+            pairs(&ranges.gaps(Interval::new(0, 10))),
+            [(0, 1), (5, 7), (10, 10)]
+        );
+        assert_eq!(pairs(&ranges.gaps(Interval::new(3, 8))), [(5, 7)]);
+        assert!(ranges.gaps(Interval::new(2, 4)).is_empty());
+        assert_eq!(pairs(&RangeSet::new().gaps(Interval::new(1, 3))), [(1, 3)]);
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = set(&[(0, 10), (20, 30)]);
+        let b = set(&[(5, 22), (29, 40)]);
+
+        assert_eq!(pairs(&a.intersection(&b)), [(5, 10), (20, 22), (29, 30)]);
+        assert_eq!(pairs(&a.union(&b)), [(0, 40)]);
+        assert_eq!(pairs(&a.difference(&b)), [(0, 4), (23, 28)]);
+        assert!(a.intersection(&set(&[(11, 19)])).is_empty());
+    }
+}
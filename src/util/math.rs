@@ -0,0 +1,212 @@
+/// greatest common divisor, always non-negative
+pub fn gcd(a: i64, b: i64) -> i64 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// lowest common multiple, zero if either value is zero, None if it
+/// doesn't fit in an i64
+pub fn lcm(a: i64, b: i64) -> Option<i64> {
+    match a == 0 || b == 0 {
+        true => Some(0),
+        false => (a / gcd(a, b)).checked_mul(b)?.checked_abs(),
+    }
+}
+
+/// gcd of every value, zero for an empty iterator
+pub fn gcd_all<I: IntoIterator<Item = i64>>(values: I) -> i64 {
+    values.into_iter().fold(0, gcd)
+}
+
+/// lcm of every value, one for an empty iterator, None if it overflows
+pub fn lcm_all<I: IntoIterator<Item = i64>>(values: I) -> Option<i64> {
+    values.into_iter().try_fold(1, lcm)
+}
+
+/// returns (g, x, y) such that a*x + b*y = g = gcd(a, b)
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    // the coefficients are bounded by the inputs, so narrowing back is safe
+    let (g, x, y) = extended_gcd_wide(a as _, b as _);
+    (g as _, x as _, y as _)
+}
+
+/// `extended_gcd`, wide enough for `crt`'s intermediate moduli
+fn extended_gcd_wide(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+
+    // keep the gcd positive
+    match old_r < 0 {
+        true => (-old_r, -old_x, -old_y),
+        false => (old_r, old_x, old_y),
+    }
+}
+
+/// base^exp mod modulus, the result is always in 0..modulus. The modulus
+/// must be positive.
+pub fn mod_pow(base: i64, mut exp: u64, modulus: i64) -> i64 {
+    if modulus == 1 {
+        return 0;
+    }
+
+    // widen so the intermediate products can't overflow
+    let m = modulus as i128;
+    let mut base = (base as i128).rem_euclid(m);
+    let mut result = 1;
+
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % m;
+        }
+        base = base * base % m;
+        exp >>= 1;
+    }
+
+    result as _
+}
+
+/// x such that a*x = 1 mod modulus, if a and modulus are coprime and the
+/// modulus is positive
+pub fn mod_inverse(a: i64, modulus: i64) -> Option<i64> {
+    if modulus <= 0 {
+        return None;
+    }
+
+    let (g, x, _) = extended_gcd(a.rem_euclid(modulus), modulus);
+    (g == 1).then(|| x.rem_euclid(modulus))
+}
+
+/// Chinese Remainder Theorem. Given pairs of (residue, modulus), find the
+/// smallest non-negative x satisfying every x = residue mod modulus, along
+/// with the lcm of the moduli. Moduli don't need to be coprime. None is
+/// returned when the congruences contradict each other, a modulus isn't
+/// positive, or the lcm doesn't fit in an i64.
+pub fn crt<I: IntoIterator<Item = (i64, i64)>>(congruences: I) -> Option<(i64, i64)> {
+    // widen so products of two moduli can't overflow
+    let mut result = (0_i128, 1_i128);
+
+    for (residue, modulus) in congruences {
+        if modulus <= 0 {
+            return None;
+        }
+
+        let (r1, m1) = result;
+        let m2 = modulus as i128;
+        let r2 = (residue as i128).rem_euclid(m2);
+
+        // m1*k = r2 - r1 (mod m2) must be solvable
+        let (g, p, _) = extended_gcd_wide(m1, m2);
+        if (r2 - r1) % g != 0 {
+            return None;
+        }
+
+        let step = m2 / g;
+        let k = ((r2 - r1) / g % step) * (p % step) % step;
+        let m = m1 * step;
+
+        // every later step relies on the lcm so far fitting in an i64
+        if m > i64::MAX as i128 {
+            return None;
+        }
+
+        result = ((r1 + m1 * k).rem_euclid(m), m);
+    }
+
+    Some((result.0 as _, result.1 as _))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_and_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(-12, 18), 6);
+        assert_eq!(gcd(0, 5), 5);
+        assert_eq!(gcd_all([12, 18, 27]), 3);
+        assert_eq!(gcd_all([]), 0);
+
+        assert_eq!(lcm(4, 6), Some(12));
+        assert_eq!(lcm(-4, 6), Some(12));
+        assert_eq!(lcm(0, 6), Some(0));
+        assert_eq!(lcm(i64::MAX, 2), None);
+        assert_eq!(lcm_all([23, 19, 13, 17]), Some(96577));
+        assert_eq!(lcm_all([]), Some(1));
+        assert_eq!(lcm_all([1_000_000_007, 998_244_353, 1_000_000_009]), None);
+    }
+
+    #[test]
+    fn extended_gcd_satisfies_bezout() {
+        for (a, b) in [(240, 46), (-240, 46), (17, 5), (0, 7), (7, 0)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, gcd(a, b));
+            assert_eq!(a * x + b * y, g, "{a}, {b}");
+        }
+    }
+
+    #[test]
+    fn mod_pow_and_inverse() {
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(-2, 3, 5), 2);
+        assert_eq!(mod_pow(5, 0, 7), 1);
+        assert_eq!(mod_pow(5, 3, 1), 0);
+        assert_eq!(mod_pow(i64::MAX, 2, 1_000_000_007), 737_564_071);
+
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(2, 4), None);
+        assert_eq!(mod_inverse(3, 0), None);
+        assert_eq!(mod_inverse(3, -5), None);
+    }
+
+    #[test]
+    fn crt_solves_congruences() {
+        assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt([(-1, 3), (3, 5)]), Some((8, 15)));
+        assert_eq!(crt([]), Some((0, 1)));
+
+        // moduli with a common factor, solvable and not
+        assert_eq!(crt([(3, 4), (1, 6)]), Some((7, 12)));
+        assert_eq!(crt([(0, 4), (1, 6)]), None);
+
+        // a modulus of one says nothing
+        assert_eq!(crt([(1, 1), (2, 3)]), Some((2, 3)));
+        assert_eq!(crt([(1, 0)]), None);
+        assert_eq!(crt([(1, -3)]), None);
+    }
+
+    #[test]
+    fn crt_with_large_moduli() {
+        let (a, b) = (1_000_000_007, 998_244_353);
+        let (x, m) = crt([(5, a), (7, b)]).unwrap();
+        assert_eq!(m, a * b);
+        assert_eq!((x % a, x % b), (5, 7));
+
+        // the lcm of all three doesn't fit
+        assert_eq!(crt([(5, a), (7, b), (9, 1_000_000_009)]), None);
+    }
+
+    #[test]
+    fn crt_matches_brute_force() {
+        let moduli = [4, 6, 9, 10, 7];
+        for residues in [[1, 3, 4, 5, 2], [3, 5, 8, 1, 0], [0, 0, 0, 0, 0]] {
+            let congruences = residues.into_iter().zip(moduli).collect::<Vec<_>>();
+            let expected = (0..lcm_all(moduli).unwrap())
+                .find(|x| congruences.iter().all(|(r, m)| x % m == *r))
+                .map(|x| (x, 1260));
+            assert_eq!(crt(congruences.clone()), expected, "{congruences:?}");
+        }
+    }
+}
@@ -1,3 +1,12 @@
+pub mod calendar;
+pub mod cpu;
+pub mod cycle;
+pub mod image;
+pub mod interval;
+pub mod math;
+pub mod ocr;
+pub mod top;
+
 use eyre::Context;
 use reqwest::{cookie::Jar, Url};
 use std::{path::PathBuf, str::FromStr, sync::Arc};
@@ -16,12 +25,8 @@ pub fn get_input(year: u64, day: u64) -> eyre::Result<String> {
         "AOC_URL (`{base_url}`) doesn't look like a url"
     );
 
-    // validate year/day
-    eyre::ensure!(
-        (2015..=2022).contains(&year),
-        "{year} is not a valid AoC year."
-    );
-    eyre::ensure!((1..=25).contains(&day), "{year} is not a valid AoC day.");
+    // validate year/day, and that it has unlocked
+    calendar::validate(year, day, calendar::now())?;
 
     // check for a cached file
     let base_dir_raw = std::env::var("AOC_INPUT_DIR").unwrap_or_else(|_| "input".into());
@@ -0,0 +1,239 @@
+/// the 4x6 font, used by most years (2016, 2019, 2021, 2022). Glyphs are
+/// separated by a blank column so they're stored trimmed, see `split_glyphs`.
+const FONT_SMALL: &[(char, &str)] = &[
+    ('A', ".##.\n#..#\n#..#\n####\n#..#\n#..#"),
+    ('B', "###.\n#..#\n###.\n#..#\n#..#\n###."),
+    ('C', ".##.\n#..#\n#...\n#...\n#..#\n.##."),
+    ('E', "####\n#...\n###.\n#...\n#...\n####"),
+    ('F', "####\n#...\n###.\n#...\n#...\n#..."),
+    ('G', ".##.\n#..#\n#...\n#.##\n#..#\n.###"),
+    ('H', "#..#\n#..#\n####\n#..#\n#..#\n#..#"),
+    ('I', "###\n.#.\n.#.\n.#.\n.#.\n###"),
+    ('J', "..##\n...#\n...#\n...#\n#..#\n.##."),
+    ('K', "#..#\n#.#.\n##..\n#.#.\n#.#.\n#..#"),
+    ('L', "#...\n#...\n#...\n#...\n#...\n####"),
+    ('O', ".##.\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('P', "###.\n#..#\n#..#\n###.\n#...\n#..."),
+    ('R', "###.\n#..#\n#..#\n###.\n#.#.\n#..#"),
+    ('S', ".###\n#...\n#...\n.##.\n...#\n###."),
+    ('U', "#..#\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('Y', "#...#\n#...#\n.#.#.\n..#..\n..#..\n..#.."),
+    ('Z', "####\n...#\n..#.\n.#..\n#...\n####"),
+];
+
+/// the 6x10 font, used by 2018 day 10
+#[rustfmt::skip]
+const FONT_LARGE: &[(char, &str)] = &[
+    (
+        'A',
+        "..##..\n.#..#.\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'B',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#....#\n#....#\n#....#\n#....#\n#####.",
+    ),
+    (
+        'C',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#....#\n.####.",
+    ),
+    (
+        'E',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'F',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'G',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#..###\n#....#\n#....#\n#...##\n.###.#",
+    ),
+    (
+        'H',
+        "#....#\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'J',
+        "...###\n....#.\n....#.\n....#.\n....#.\n....#.\n....#.\n#...#.\n#...#.\n.###..",
+    ),
+    (
+        'K',
+        "#....#\n#...#.\n#..#..\n#.#...\n##....\n##....\n#.#...\n#..#..\n#...#.\n#....#",
+    ),
+    (
+        'L',
+        "#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'N',
+        "#....#\n##...#\n##...#\n#.#..#\n#.#..#\n#..#.#\n#..#.#\n#...##\n#...##\n#....#",
+    ),
+    (
+        'P',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'R',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#..#..\n#...#.\n#...#.\n#....#\n#....#",
+    ),
+    (
+        'X',
+        "#....#\n#....#\n.#..#.\n.#..#.\n..##..\n..##..\n.#..#.\n.#..#.\n#....#\n#....#",
+    ),
+    (
+        'Z',
+        "######\n.....#\n.....#\n....#.\n...#..\n..#...\n.#....\n#.....\n#.....\n######",
+    ),
+];
+
+/// a single glyph, stored row by row
+type Glyph = Vec<Vec<bool>>;
+
+fn parse_glyph(raw: &str) -> Glyph {
+    raw.lines()
+        .map(|row| row.chars().map(|c| c == '#').collect())
+        .collect()
+}
+
+/// split a grid into glyphs on fully blank columns, dropping the blanks
+fn split_glyphs(rows: &[Vec<bool>]) -> Vec<Glyph> {
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let lit = |x: usize| rows.iter().any(|row| row.get(x).copied().unwrap_or(false));
+
+    let mut result = Vec::new();
+    let mut x = 0;
+
+    while x < width {
+        // skip the gap between glyphs
+        if !lit(x) {
+            x += 1;
+            continue;
+        }
+
+        // consume columns until the next gap
+        let start = x;
+        while x < width && lit(x) {
+            x += 1;
+        }
+
+        result.push(
+            rows.iter()
+                .map(|row| {
+                    (start..x)
+                        .map(|i| row.get(i).copied().unwrap_or(false))
+                        .collect()
+                })
+                .collect(),
+        );
+    }
+
+    result
+}
+
+/// recognise the letters drawn in a grid of pixels, `true` being lit. The
+/// font is picked by height, 6 rows for the small font or 10 for the large.
+/// Returns None if the height is unknown or any glyph can't be matched.
+pub fn recognise(rows: &[Vec<bool>]) -> Option<String> {
+    let font = match rows.len() {
+        6 => FONT_SMALL,
+        10 => FONT_LARGE,
+        _ => return None,
+    };
+
+    let glyphs = split_glyphs(rows);
+    if glyphs.is_empty() {
+        return None;
+    }
+
+    glyphs
+        .iter()
+        .map(|glyph| {
+            font.iter()
+                .find(|(_, raw)| &parse_glyph(raw) == glyph)
+                .map(|(letter, _)| *letter)
+        })
+        .collect()
+}
+
+/// convenience wrapper around `recognise` for text grids, `#` being lit
+pub fn recognise_str(input: &str) -> Option<String> {
+    let rows = input
+        .lines()
+        .map(|line| line.chars().map(|c| c == '#').collect())
+        .collect::<Vec<_>>();
+
+    recognise(&rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// draw a word in a font, glyphs `gap` columns apart
+    fn draw(word: &str, font: &[(char, &str)], gap: usize) -> Vec<Vec<bool>> {
+        let glyphs = word
+            .chars()
+            .map(|c| parse_glyph(font.iter().find(|(l, _)| *l == c).unwrap().1))
+            .collect::<Vec<_>>();
+
+        (0..glyphs[0].len())
+            .map(|y| {
+                glyphs
+                    .iter()
+                    .flat_map(|glyph| glyph[y].iter().copied().chain(vec![false; gap]))
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reads_a_crt_screen() {
+        let screen = "\
+####.#..#...##.####.###....##.####.####.
+...#.#.#.....#.#....#..#....#.#.......#.
+..#..##......#.###..###.....#.###....#..
+.#...#.#.....#.#....#..#....#.#.....#...
+#....#.#..#..#.#....#..#.#..#.#....#....
+####.#..#..##..#....###...##..#....####.";
+
+        assert_eq!(recognise_str(screen).as_deref(), Some("ZKJFBJFZ"));
+    }
+
+    #[test]
+    fn every_glyph_reads_back() {
+        let small = FONT_SMALL.iter().map(|(c, _)| *c).collect::<String>();
+        assert_eq!(recognise(&draw(&small, FONT_SMALL, 1)), Some(small));
+
+        // the large font is spaced further apart
+        let large = FONT_LARGE.iter().map(|(c, _)| *c).collect::<String>();
+        assert_eq!(recognise(&draw(&large, FONT_LARGE, 2)), Some(large));
+        assert_eq!(
+            recognise(&draw("HNZ", FONT_LARGE, 2)).as_deref(),
+            Some("HNZ")
+        );
+    }
+
+    #[test]
+    fn unknown_glyphs_are_none() {
+        // a lone dot isn't a letter, even alongside ones that are
+        let mut rows = draw("AB", FONT_SMALL, 1);
+        rows[0].extend([false, true]);
+        for row in &mut rows[1..] {
+            row.extend([false, false]);
+        }
+        assert_eq!(recognise(&rows), None);
+
+        // nothing lit at all
+        assert_eq!(recognise(&vec![vec![false; 10]; 6]), None);
+    }
+
+    #[test]
+    fn only_known_heights_are_read() {
+        let mut rows = draw("AB", FONT_SMALL, 1);
+        rows.push(vec![false; rows[0].len()]);
+        assert_eq!(recognise(&rows), None);
+
+        assert_eq!(recognise(&draw("AB", FONT_SMALL, 1)[..5]), None);
+        assert_eq!(recognise(&[]), None);
+    }
+}
@@ -0,0 +1,26 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+/// the `k` largest items, largest first. Only `k` items are held at a time,
+/// in a min-heap whose smallest is evicted whenever something bigger arrives.
+pub fn top_k<T: Ord>(items: impl IntoIterator<Item = T>, k: usize) -> Vec<T> {
+    if k == 0 {
+        return Vec::new();
+    }
+
+    let mut heap = BinaryHeap::with_capacity(k + 1);
+    for item in items {
+        match heap.len() < k {
+            true => heap.push(Reverse(item)),
+            false => {
+                // SAFETY: k is non-zero, so the heap is full and not empty
+                if item > heap.peek().unwrap().0 {
+                    heap.pop();
+                    heap.push(Reverse(item));
+                }
+            }
+        }
+    }
+
+    // the heap sorts ascending by `Reverse`, so largest first
+    heap.into_sorted_vec().into_iter().map(|r| r.0).collect()
+}
@@ -0,0 +1,79 @@
+use std::{
+    io::{IsTerminal, Write},
+    time::Duration,
+};
+
+/// a day's frames, borrowing from its parsed input
+pub type Frames<'a> = Box<dyn Iterator<Item = String> + 'a>;
+
+const CLEAR: &str = "\x1b[H\x1b[2J";
+const HIDE_CURSOR: &str = "\x1b[?25l";
+const SHOW_CURSOR: &str = "\x1b[?25h";
+
+/// how to play back a day's frames
+#[derive(Debug, Clone, Copy)]
+pub struct Player {
+    // frames drawn per second
+    pub fps: u32,
+
+    // only draw every `step`th frame, the last is always drawn
+    pub step: usize,
+}
+
+impl Default for Player {
+    fn default() -> Self {
+        Self { fps: 30, step: 1 }
+    }
+}
+
+impl Player {
+    /// only a terminal can be drawn over, check before building any frames
+    pub fn can_play(&self) -> bool {
+        std::io::stdout().is_terminal()
+    }
+
+    /// draw each frame over the last, doing nothing if stdout isn't a terminal
+    pub fn play(&self, title: &str, frames: Frames<'_>) -> eyre::Result<()> {
+        if !self.can_play() {
+            return Ok(());
+        }
+
+        let stdout = std::io::stdout();
+        let step = self.step.max(1);
+        let mut out = stdout.lock();
+        write!(out, "{HIDE_CURSOR}")?;
+
+        // hold on to the latest frame, so the final state is always shown
+        let mut pending = None;
+        let result = frames.enumerate().try_for_each(|(index, frame)| {
+            pending = Some((index, frame));
+            match index % step == 0 {
+                true => self.draw(&mut out, title, pending.take()),
+                false => Ok(()),
+            }
+        });
+
+        let result = result.and_then(|_| self.draw(&mut out, title, pending));
+        write!(out, "{SHOW_CURSOR}")?;
+        out.flush()?;
+
+        result
+    }
+
+    fn draw(
+        &self,
+        out: &mut impl Write,
+        title: &str,
+        frame: Option<(usize, String)>,
+    ) -> eyre::Result<()> {
+        let Some((index, frame)) = frame else {
+            return Ok(());
+        };
+
+        write!(out, "{CLEAR}{title} (frame {})\n\n{frame}\n", index + 1)?;
+        out.flush()?;
+        std::thread::sleep(Duration::from_secs_f64(1.0 / self.fps.max(1) as f64));
+
+        Ok(())
+    }
+}
@@ -1,50 +1,45 @@
-// here's the iterable version
-// fn get_input() -> eyre::Result<Vec<u64>> {
-//     Ok(crate::util::get_input(2022, 1)?
-//         .split("\n\n")
-//         .map(|elf| {
-//             Ok(elf
-//                 .split_whitespace()
-//                 .map(|line| Ok(line.parse()?))
-//                 .collect::<eyre::Result<Vec<_>>>()?
-//                 .iter()
-//                 .sum())
-//         })
-//         .collect::<eyre::Result<Vec<_>>>()?)
-// }
-
-pub fn get_input() -> eyre::Result<Vec<u64>> {
-    let input = crate::util::get_input(2022, 1)?;
+use crate::{runner::Solution, util::top::top_k};
 
+/// fold each elf's snacks into a total as the lines stream past, a blank line
+/// ends an elf
+pub fn parse_totals(input: &str) -> eyre::Result<Vec<u64>> {
     let mut results = Vec::new();
-    for elf in input.split("\n\n") {
-        let values = elf
-            .lines()
-            .map(|line| Ok(line.parse()?))
-            .collect::<eyre::Result<Vec<_>>>()?;
-
-        results.push(values.iter().sum())
+    let mut current = None;
+
+    for (index, line) in input.lines().enumerate() {
+        match line.trim().is_empty() {
+            true => results.extend(current.take()),
+            false => {
+                let calories = line
+                    .trim()
+                    .parse::<u64>()
+                    .map_err(|e| eyre::eyre!("line {}: {e}", index + 1))?;
+                *current.get_or_insert(0) += calories;
+            }
+        }
     }
+    results.extend(current);
 
     Ok(results)
 }
 
-pub fn part_one(input: &[u64]) -> u64 {
-    *input.iter().max().unwrap()
+pub fn get_input() -> eyre::Result<Vec<u64>> {
+    parse_totals(&crate::util::get_input(2022, 1)?)
 }
 
-pub fn part_two(input: &[u64]) -> u64 {
-    let mut input = input.to_vec(); // clone required, sorting ahead
-    input.sort_unstable();
-
-    input.iter().rev().take(3).sum()
+/// calories carried by the `k` best stocked elves
+pub fn top_calories(input: &[u64], k: usize) -> u64 {
+    top_k(input.iter().copied(), k).iter().sum()
 }
 
-pub fn main() -> eyre::Result<()> {
-    let input = get_input()?;
+pub fn part_one(input: &[u64]) -> u64 {
+    top_calories(input, 1)
+}
 
-    tracing::info!("Y2022D01P01: {}", part_one(&input));
-    tracing::info!("Y2022D01P02: {}", part_two(&input));
+pub fn part_two(input: &[u64]) -> u64 {
+    top_calories(input, 3)
+}
 
-    Ok(())
+pub fn solution() -> Solution {
+    Solution::new(2022, 1, get_input, |i| Ok(part_one(i)), |i| Ok(part_two(i)))
 }
@@ -1,117 +1,243 @@
-use std::str::FromStr;
+use std::{fmt::Display, str::FromStr};
 
-#[derive(Debug)]
+use crate::runner::Solution;
+
+/// each move beats the one before it in the cycle, for the extended game a
+/// move beats those an odd distance behind it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Move {
     Rock,
     Paper,
     Scissors,
+    Spock,
+    Lizard,
+}
+
+impl Move {
+    /// step `by` places around the cycle of moves allowed by `rules`
+    pub fn shift(self, by: i64, rules: Rules) -> Self {
+        let moves = rules.moves();
+        moves[(self as i64 + by).rem_euclid(moves.len() as i64) as usize]
+    }
+
+    /// the result of playing this move against `other`
+    pub fn against(self, other: Self, rules: Rules) -> Outcome {
+        match (self as i64 - other as i64).rem_euclid(rules.moves().len() as i64) {
+            0 => Outcome::Draw,
+            d if d % 2 == 1 => Outcome::Win,
+            _ => Outcome::Lose,
+        }
+    }
+
+    /// the move to play against `opponent` to get `outcome`
+    pub fn to_reach(opponent: Self, outcome: Outcome, rules: Rules) -> Self {
+        match outcome {
+            Outcome::Lose => opponent.shift(-1, rules),
+            Outcome::Draw => opponent,
+            Outcome::Win => opponent.shift(1, rules),
+        }
+    }
+
+    /// a move from the first column, as long as `rules` allows it. Spock and
+    /// Lizard would otherwise wrap around onto the classic moves.
+    pub fn parse(s: &str, rules: Rules) -> eyre::Result<Self> {
+        let parsed = match s.to_lowercase().trim() {
+            "a" => Self::Rock,
+            "b" => Self::Paper,
+            "c" => Self::Scissors,
+            "d" => Self::Spock,
+            "e" => Self::Lizard,
+            other => eyre::bail!("unrecognised move: {other}"),
+        };
+
+        eyre::ensure!(
+            rules.moves().contains(&parsed),
+            "{parsed} isn't played under {rules:?} rules"
+        );
+        Ok(parsed)
+    }
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format!("{self:?}").to_lowercase())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    Lose,
+    Draw,
+    Win,
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format!("{self:?}").to_lowercase())
+    }
+}
+
+/// which moves are in play
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rules {
+    Classic,
+
+    // rock-paper-scissors-lizard-spock
+    Extended,
+}
+
+impl Rules {
+    /// the moves in cycle order
+    pub fn moves(self) -> &'static [Move] {
+        match self {
+            Self::Classic => &[Move::Rock, Move::Paper, Move::Scissors],
+            Self::Extended => &[
+                Move::Rock,
+                Move::Paper,
+                Move::Scissors,
+                Move::Spock,
+                Move::Lizard,
+            ],
+        }
+    }
+}
+
+/// points for the shape played and the outcome, indexed by `Move` and `Outcome`
+#[derive(Debug, Clone, Copy)]
+pub struct Scoring {
+    pub shapes: [u64; 5],
+    pub outcomes: [u64; 3],
+}
+
+impl Default for Scoring {
+    fn default() -> Self {
+        Self {
+            shapes: [1, 2, 3, 4, 5],
+            outcomes: [0, 3, 6],
+        }
+    }
+}
+
+impl Scoring {
+    pub fn score(&self, player: Move, opponent: Move, rules: Rules) -> u64 {
+        self.shapes[player as usize] + self.outcomes[player.against(opponent, rules) as usize]
+    }
 }
 
-impl FromStr for Move {
+/// the second column of the strategy guide, its meaning is up for debate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hint {
+    X,
+    Y,
+    Z,
+}
+
+impl FromStr for Hint {
     type Err = eyre::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_lowercase().trim() {
-            "a" | "x" => Ok(Self::Rock),
-            "b" | "y" => Ok(Self::Paper),
-            "c" | "z" => Ok(Self::Scissors),
-            other => eyre::bail!("unrecognised move: {other}"),
+            "x" => Ok(Self::X),
+            "y" => Ok(Self::Y),
+            "z" => Ok(Self::Z),
+            other => eyre::bail!("unrecognised hint: {other}"),
         }
     }
 }
 
-pub struct Game {
-    pub opponent: Move,
-    pub player: Move,
+/// a reading of the strategy guide, each hint maps to a move or an outcome
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpretation {
+    Moves([Move; 3]),
+    Outcomes([Outcome; 3]),
 }
 
-impl Game {
-    pub fn score(&self) -> u64 {
-        // start with the player move's value
-        let mut result = match &self.player {
-            Move::Rock => 1,
-            Move::Paper => 2,
-            Move::Scissors => 3,
-        };
+impl Interpretation {
+    /// the elf's first explanation (part 1)
+    pub const AS_MOVES: Self = Self::Moves([Move::Rock, Move::Paper, Move::Scissors]);
 
-        // now add whether the player won/drew/lost
-        result += match (&self.player, &self.opponent) {
-            // win
-            (Move::Rock, Move::Scissors)
-            | (Move::Paper, Move::Rock)
-            | (Move::Scissors, Move::Paper) => 6,
-
-            // draw
-            (Move::Rock, Move::Rock)
-            | (Move::Paper, Move::Paper)
-            | (Move::Scissors, Move::Scissors) => 3,
-
-            // loss
-            (Move::Rock, Move::Paper)
-            | (Move::Paper, Move::Scissors)
-            | (Move::Scissors, Move::Rock) => 0,
-        };
+    /// what the elf actually meant (part 2)
+    pub const AS_OUTCOMES: Self = Self::Outcomes([Outcome::Lose, Outcome::Draw, Outcome::Win]);
+
+    /// every way the hints could map onto distinct moves or outcomes
+    pub fn all(rules: Rules) -> Vec<Self> {
+        let moves = rules.moves();
+        let outcomes = [Outcome::Lose, Outcome::Draw, Outcome::Win];
+
+        let mut result = Vec::new();
+        for x in 0..moves.len() {
+            for y in (0..moves.len()).filter(|y| *y != x) {
+                for z in (0..moves.len()).filter(|z| *z != x && *z != y) {
+                    result.push(Self::Moves([moves[x], moves[y], moves[z]]));
+
+                    // only three outcomes to go round
+                    if z < 3 && y < 3 && x < 3 {
+                        result.push(Self::Outcomes([outcomes[x], outcomes[y], outcomes[z]]));
+                    }
+                }
+            }
+        }
 
         result
     }
 
-    pub fn score_with_strategy(&self) -> u64 {
-        match &self.player {
-            // must lose
-            Move::Rock => match &self.opponent {
-                Move::Rock => Game {
-                    opponent: Move::Rock,
-                    player: Move::Scissors,
-                },
-                Move::Paper => Game {
-                    opponent: Move::Paper,
-                    player: Move::Rock,
-                },
-                Move::Scissors => Game {
-                    opponent: Move::Scissors,
-                    player: Move::Paper,
-                },
-            },
-
-            // must draw
-            Move::Paper => match &self.opponent {
-                Move::Rock => Game {
-                    opponent: Move::Rock,
-                    player: Move::Rock,
-                },
-                Move::Paper => Game {
-                    opponent: Move::Paper,
-                    player: Move::Paper,
-                },
-                Move::Scissors => Game {
-                    opponent: Move::Scissors,
-                    player: Move::Scissors,
-                },
-            },
-
-            // must win
-            Move::Scissors => match &self.opponent {
-                Move::Rock => Game {
-                    opponent: Move::Rock,
-                    player: Move::Paper,
-                },
-                Move::Paper => Game {
-                    opponent: Move::Paper,
-                    player: Move::Scissors,
-                },
-                Move::Scissors => Game {
-                    opponent: Move::Scissors,
-                    player: Move::Rock,
-                },
-            },
+    /// the move this reading says to play
+    pub fn player(&self, game: &Game, rules: Rules) -> Move {
+        match self {
+            Self::Moves(moves) => moves[game.hint as usize],
+            Self::Outcomes(outcomes) => {
+                Move::to_reach(game.opponent, outcomes[game.hint as usize], rules)
+            }
         }
-        .score()
     }
 }
 
-pub fn get_input() -> eyre::Result<Vec<Game>> {
-    let input = crate::util::get_input(2022, 2)?;
+impl Display for Interpretation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Moves([x, y, z]) => write!(f, "X={x} Y={y} Z={z}"),
+            Self::Outcomes([x, y, z]) => write!(f, "X={x} Y={y} Z={z}"),
+        }
+    }
+}
+
+pub struct Game {
+    pub opponent: Move,
+    pub hint: Hint,
+}
+
+impl Game {
+    pub fn score(&self, interpretation: Interpretation, scoring: &Scoring, rules: Rules) -> u64 {
+        scoring.score(interpretation.player(self, rules), self.opponent, rules)
+    }
+}
+
+/// the total score of following the guide under `interpretation`
+pub fn total(
+    input: &[Game],
+    interpretation: Interpretation,
+    scoring: &Scoring,
+    rules: Rules,
+) -> u64 {
+    input
+        .iter()
+        .map(|g| g.score(interpretation, scoring, rules))
+        .sum()
+}
+
+/// score every interpretation of the guide, best first
+pub fn analyse(input: &[Game], scoring: &Scoring, rules: Rules) -> Vec<(Interpretation, u64)> {
+    let mut results = Interpretation::all(rules)
+        .into_iter()
+        .map(|i| (i, total(input, i, scoring, rules)))
+        .collect::<Vec<_>>();
+    results.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+
+    results
+}
 
+/// a strategy guide, one game per line, its moves checked against `rules`
+pub fn parse_games(input: &str, rules: Rules) -> eyre::Result<Vec<Game>> {
     let mut results = Vec::new();
     for line in input.lines() {
         let (x, y) = line
@@ -120,27 +246,60 @@ pub fn get_input() -> eyre::Result<Vec<Game>> {
             .ok_or_else(|| eyre::eyre!("{line} was poorly formatted!"))?;
 
         results.push(Game {
-            opponent: x.parse()?,
-            player: y.parse()?,
+            opponent: Move::parse(x, rules)?,
+            hint: y.parse()?,
         })
     }
 
     Ok(results)
 }
 
+pub fn get_input() -> eyre::Result<Vec<Game>> {
+    parse_games(&crate::util::get_input(2022, 2)?, Rules::Classic)
+}
+
 pub fn part_one(input: &[Game]) -> u64 {
-    input.iter().map(|g| g.score()).sum()
+    total(
+        input,
+        Interpretation::AS_MOVES,
+        &Scoring::default(),
+        Rules::Classic,
+    )
 }
 
 pub fn part_two(input: &[Game]) -> u64 {
-    input.iter().map(|g| g.score_with_strategy()).sum()
+    total(
+        input,
+        Interpretation::AS_OUTCOMES,
+        &Scoring::default(),
+        Rules::Classic,
+    )
+}
+
+pub fn solution() -> Solution {
+    Solution::new(2022, 2, get_input, |i| Ok(part_one(i)), |i| Ok(part_two(i)))
 }
 
-pub fn main() -> eyre::Result<()> {
-    let input = get_input()?;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    tracing::info!("Y2022D02P01: {}", part_one(&input));
-    tracing::info!("Y2022D02P02: {}", part_two(&input));
+    const EXAMPLE: &str = "A Y\nB X\nC Z\n";
 
-    Ok(())
+    #[test]
+    fn example() {
+        let games = parse_games(EXAMPLE, Rules::Classic).unwrap();
+        assert_eq!(part_one(&games), 15);
+        assert_eq!(part_two(&games), 12);
+    }
+
+    #[test]
+    fn moves_are_checked_against_the_rules() {
+        assert!(parse_games("D X\n", Rules::Classic).is_err());
+        assert!(parse_games("E Z\n", Rules::Classic).is_err());
+
+        let games = parse_games("D X\nE Z\n", Rules::Extended).unwrap();
+        assert_eq!(games[0].opponent, Move::Spock);
+        assert_eq!(games[1].opponent, Move::Lizard);
+    }
 }
@@ -1,67 +1,174 @@
-use std::collections::HashSet;
+use std::ops::BitAnd;
 
-pub fn score_letter(c: &char) -> u64 {
-    match c.is_ascii_uppercase() {
-        true => ((*c as u8) - 38) as _,
-        false => ((*c as u8) - 96) as _,
+use crate::runner::Solution;
+
+/// a-z are 1 through 26, A-Z are 27 through 52
+pub fn priority(c: char) -> eyre::Result<u64> {
+    match c {
+        'a'..='z' => Ok((c as u8 - b'a') as u64 + 1),
+        'A'..='Z' => Ok((c as u8 - b'A') as u64 + 27),
+        other => Err(eyre::eyre!("`{other}` is not an item")),
     }
 }
 
-pub fn get_input() -> eyre::Result<Vec<String>> {
-    Ok(crate::util::get_input(2022, 3)?
-        .lines()
-        .map(From::from)
-        .collect::<Vec<_>>())
+/// a set of items, bit `n` is set if the item with priority `n + 1` is present
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Items(pub u64);
+
+impl Items {
+    /// every possible item, a-z and A-Z
+    pub const ALL: Self = Self((1 << 52) - 1);
+
+    pub fn parse(items: &str) -> eyre::Result<Self> {
+        items.chars().try_fold(Self::default(), |set, c| {
+            Ok(Self(set.0 | 1 << (priority(c)? - 1)))
+        })
+    }
+
+    /// the priority of each item present, lowest first
+    pub fn priorities(self) -> impl Iterator<Item = u64> {
+        (0..52)
+            .filter(move |bit| self.0 & 1 << bit != 0)
+            .map(|bit| bit + 1)
+    }
 }
 
-pub fn part_one(input: &[String]) -> eyre::Result<u64> {
-    let mut result = 0;
+impl BitAnd for Items {
+    type Output = Self;
 
-    for line in input {
-        let (a, b) = line.split_at(line.len() / 2);
-        let x = a.chars().collect::<HashSet<_>>();
-        let y = b.chars().collect::<HashSet<_>>();
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
+
+/// items found in every one of the sets
+pub fn common(sets: impl IntoIterator<Item = Items>) -> Items {
+    sets.into_iter().fold(Items::ALL, BitAnd::bitand)
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Rucksack {
+    pub left: Items,
+    pub right: Items,
+}
 
-        result += &x.intersection(&y).map(score_letter).sum();
+impl Rucksack {
+    /// both compartments together
+    pub fn items(&self) -> Items {
+        Items(self.left.0 | self.right.0)
     }
+}
 
-    Ok(result)
+/// the items common to each group of `size` rucksacks, every rucksack must
+/// belong to a group
+pub fn common_in_groups(rucksacks: &[Rucksack], size: usize) -> eyre::Result<Vec<Items>> {
+    eyre::ensure!(size > 0, "groups must have at least one rucksack");
+    eyre::ensure!(
+        rucksacks.len().is_multiple_of(size),
+        "{} rucksacks can't be split into groups of {size}",
+        rucksacks.len()
+    );
+
+    Ok(rucksacks
+        .chunks(size)
+        .map(|group| common(group.iter().map(Rucksack::items)))
+        .collect())
 }
 
-pub fn part_two(input: &[String]) -> eyre::Result<u64> {
-    let mut result = 0;
+/// one rucksack per line, split evenly between its two compartments
+pub fn parse_rucksacks(input: &str) -> eyre::Result<Vec<Rucksack>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            eyre::ensure!(
+                line.len().is_multiple_of(2),
+                "line {}: `{line}` can't be split into two compartments",
+                index + 1
+            );
+
+            // check every item before splitting, so the split lands on a char
+            if let Some(c) = line.chars().find(|c| !c.is_ascii_alphabetic()) {
+                eyre::bail!("line {}: `{c}` is not an item", index + 1);
+            }
+            let (left, right) = line.split_at(line.len() / 2);
+
+            Ok(Rucksack {
+                left: Items::parse(left)?,
+                right: Items::parse(right)?,
+            })
+        })
+        .collect()
+}
 
-    for chunk in input.chunks(3) {
-        let mut it = chunk.iter();
+pub fn get_input() -> eyre::Result<Vec<Rucksack>> {
+    parse_rucksacks(&crate::util::get_input(2022, 3)?)
+}
 
-        // grab the first element
-        let head = it
-            .next()
-            .ok_or_else(|| eyre::eyre!("unable to get head of chunk"))?
-            .chars()
-            .collect::<HashSet<_>>();
+pub fn part_one(input: &[Rucksack]) -> u64 {
+    input
+        .iter()
+        .flat_map(|r| common([r.left, r.right]).priorities())
+        .sum()
+}
 
-        // now grab the rest
-        let tail = it
-            .map(|line| line.chars().collect::<HashSet<_>>())
-            .collect::<Vec<_>>();
+pub fn part_two(input: &[Rucksack]) -> eyre::Result<u64> {
+    Ok(common_in_groups(input, 3)?
+        .into_iter()
+        .flat_map(Items::priorities)
+        .sum())
+}
 
-        // find the unique char among all three lines
-        let unique = tail.iter().fold(head, |i, v| {
-            i.intersection(&v).copied().collect::<HashSet<_>>()
-        });
+pub fn solution() -> Solution {
+    Solution::new(2022, 3, get_input, |i| Ok(part_one(i)), |i| part_two(i))
+}
 
-        result += unique.iter().map(score_letter).sum::<u64>();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw
+";
+
+    #[test]
+    fn example() {
+        let rucksacks = parse_rucksacks(EXAMPLE).unwrap();
+        assert_eq!(part_one(&rucksacks), 157);
+        assert_eq!(part_two(&rucksacks).unwrap(), 70);
     }
 
-    Ok(result)
-}
+    #[test]
+    fn odd_lines_are_rejected() {
+        let error = parse_rucksacks("abab\nabc\n").unwrap_err();
+        assert!(error.to_string().starts_with("line 2:"), "{error}");
+    }
 
-pub fn main() -> eyre::Result<()> {
-    let input = get_input()?;
+    #[test]
+    fn non_letters_are_rejected() {
+        assert!(parse_rucksacks("ab1b").is_err());
+        assert!(parse_rucksacks("abéé").is_err());
+        assert!(parse_rucksacks("ab b").is_err());
+    }
 
-    tracing::info!("Y2022D03P01: {}", part_one(&input)?);
-    tracing::info!("Y2022D03P02: {}", part_two(&input)?);
+    #[test]
+    fn partial_groups_are_rejected() {
+        let rucksacks = parse_rucksacks(EXAMPLE).unwrap();
+        assert!(common_in_groups(&rucksacks[..4], 3).is_err());
+        assert!(common_in_groups(&rucksacks, 0).is_err());
+        assert_eq!(common_in_groups(&rucksacks, 2).unwrap().len(), 3);
+    }
 
-    Ok(())
+    #[test]
+    fn all_holds_every_item() {
+        assert_eq!(
+            Items::ALL.priorities().collect::<Vec<_>>(),
+            (1..=52).collect::<Vec<_>>()
+        );
+    }
 }
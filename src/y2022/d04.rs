@@ -1,19 +1,11 @@
-use std::{ops::RangeInclusive, str::FromStr};
+use std::str::FromStr;
+
+use crate::{runner::Solution, util::interval::Interval};
 
 #[derive(Debug)]
 pub struct Section {
-    left: (u64, u64),
-    right: (u64, u64),
-}
-
-impl Section {
-    pub fn range_left(&self) -> RangeInclusive<u64> {
-        self.left.0..=self.left.1
-    }
-
-    pub fn range_right(&self) -> RangeInclusive<u64> {
-        self.right.0..=self.right.1
-    }
+    pub left: Interval,
+    pub right: Interval,
 }
 
 impl FromStr for Section {
@@ -22,19 +14,19 @@ impl FromStr for Section {
     fn from_str(line: &str) -> eyre::Result<Self> {
         let (left, right) = line
             .split_once(',')
-            .ok_or_else(|| eyre::eyre!("{line} is missing a hyphen"))?;
+            .ok_or_else(|| eyre::eyre!("{line} is missing a comma"))?;
 
         let (a, b) = left
             .split_once('-')
-            .ok_or_else(|| eyre::eyre!("{line} is missing a left comma"))?;
+            .ok_or_else(|| eyre::eyre!("{line} is missing a left hyphen"))?;
 
         let (c, d) = right
             .split_once('-')
-            .ok_or_else(|| eyre::eyre!("{line} is missing a right comma"))?;
+            .ok_or_else(|| eyre::eyre!("{line} is missing a right hyphen"))?;
 
         Ok(Self {
-            left: (a.parse()?, b.parse()?),
-            right: (c.parse()?, d.parse()?),
+            left: Interval::new(a.parse()?, b.parse()?),
+            right: Interval::new(c.parse()?, d.parse()?),
         })
     }
 }
@@ -50,11 +42,8 @@ pub fn part_one(input: &[Section]) -> u64 {
     input
         .iter()
         .filter(|section| {
-            let left = section.range_left();
-            let right = section.range_right();
-
-            left.clone().into_iter().all(|n| right.contains(&n))
-                || right.clone().into_iter().all(|n| left.contains(&n))
+            section.left.contains_interval(&section.right)
+                || section.right.contains_interval(&section.left)
         })
         .count() as _
 }
@@ -62,20 +51,10 @@ pub fn part_one(input: &[Section]) -> u64 {
 pub fn part_two(input: &[Section]) -> u64 {
     input
         .iter()
-        .filter(|section| {
-            let left = section.range_left();
-            let right = section.range_right();
-
-            left.into_iter().any(|n| right.contains(&n))
-        })
+        .filter(|section| section.left.overlaps(&section.right))
         .count() as _
 }
 
-pub fn main() -> eyre::Result<()> {
-    let input = get_input()?;
-
-    tracing::info!("Y2022D04P01: {}", part_one(&input));
-    tracing::info!("Y2022D04P02: {}", part_two(&input));
-
-    Ok(())
+pub fn solution() -> Solution {
+    Solution::new(2022, 4, get_input, |i| Ok(part_one(i)), |i| Ok(part_two(i)))
 }
@@ -1,12 +1,104 @@
-use std::collections::HashMap;
+use std::fmt::Display;
 
-pub type Columns = HashMap<u64, Vec<char>>;
+use crate::{runner::Solution, visualize::Frames};
+
+/// the stacks of crates, column `n` is stored at index `n - 1`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Columns {
+    pub stacks: Vec<Vec<char>>,
+}
+
+impl Columns {
+    /// the crate on top of each column, in order
+    pub fn tops(&self) -> eyre::Result<String> {
+        self.stacks
+            .iter()
+            .enumerate()
+            .map(|(index, stack)| {
+                stack
+                    .last()
+                    .ok_or_else(|| eyre::eyre!("column {} is empty!", index + 1))
+            })
+            .collect()
+    }
+
+    /// apply a command that has already been validated
+    fn apply(&mut self, crane: &impl Crane, command: &Command) {
+        let crates = crane.lift(&mut self.stacks[command.from - 1], command.quantity);
+        self.stacks[command.to - 1].extend(crates);
+    }
+}
+
+impl Display for Columns {
+    /// draw the columns the same way the puzzle does
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let height = self.stacks.iter().map(Vec::len).max().unwrap_or(0);
+
+        for level in (0..height).rev() {
+            let row = self
+                .stacks
+                .iter()
+                .map(|stack| match stack.get(level) {
+                    Some(c) => format!("[{c}]"),
+                    None => "   ".into(),
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            writeln!(f, "{}", row.trim_end())?;
+        }
+
+        let numbers = (1..=self.stacks.len())
+            .map(|n| format!(" {n} "))
+            .collect::<Vec<_>>()
+            .join(" ");
+        write!(f, "{}", numbers.trim_end())
+    }
+}
+
+/// a crane model, deciding what order lifted crates are set down in
+pub trait Crane {
+    /// lift `quantity` crates off the top of `stack`, returned in the order
+    /// they'll be put down
+    fn lift(&self, stack: &mut Vec<char>, quantity: usize) -> Vec<char>;
+}
+
+/// moves crates one at a time, reversing them (part 1)
+#[derive(Debug, Clone, Copy)]
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn lift(&self, stack: &mut Vec<char>, quantity: usize) -> Vec<char> {
+        let mut crates = stack.split_off(stack.len() - quantity);
+        crates.reverse();
+        crates
+    }
+}
+
+/// moves crates all at once, keeping their order (part 2)
+#[derive(Debug, Clone, Copy)]
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn lift(&self, stack: &mut Vec<char>, quantity: usize) -> Vec<char> {
+        stack.split_off(stack.len() - quantity)
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct Command {
-    pub quantity: u64,
-    pub from: u64,
-    pub to: u64,
+    pub quantity: usize,
+    pub from: usize,
+    pub to: usize,
+}
+
+impl Display for Command {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "move {} from {} to {}",
+            self.quantity, self.from, self.to
+        )
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -16,89 +108,121 @@ pub struct World {
 }
 
 impl World {
-    /// apply rules in accordance to a cratemover9000 (part 1)
-    pub fn cratemover_9000(&mut self) -> eyre::Result<&mut Self> {
-        for command in self.commands.iter() {
-            for _ in 0..command.quantity {
-                // pop items one by one into the destination
-                let char = self
-                    .columns
-                    .get_mut(&command.from)
-                    .ok_or_else(|| eyre::eyre!("unrecognised column {}", &command.from))?
-                    .pop()
-                    .ok_or_else(|| eyre::eyre!("no value to pop from index: {}", &command.from))?;
-
-                self.columns
-                    .get_mut(&command.to)
-                    .ok_or_else(|| eyre::eyre!("unrecognised column {}", &command.to))?
-                    .push(char);
+    /// dry run every command, checking the columns exist and have enough
+    /// crates. Either crane moves the same number of crates, so only the
+    /// heights need tracking.
+    pub fn validate(&self) -> eyre::Result<()> {
+        let mut heights = self.columns.stacks.iter().map(Vec::len).collect::<Vec<_>>();
+
+        for (index, command) in self.commands.iter().enumerate() {
+            for column in [command.from, command.to] {
+                eyre::ensure!(
+                    (1..=heights.len()).contains(&column),
+                    "command {} (`{command}`): there is no column {column}",
+                    index + 1
+                );
             }
+
+            let available = heights[command.from - 1];
+            eyre::ensure!(
+                available >= command.quantity,
+                "command {} (`{command}`): column {} only has {available} crates",
+                index + 1,
+                command.from
+            );
+
+            heights[command.from - 1] -= command.quantity;
+            heights[command.to - 1] += command.quantity;
         }
 
-        Ok(self)
+        Ok(())
     }
 
-    /// apply rules in accordance to a cratemover9001 (part 2)
-    pub fn cratemover_9001(&mut self) -> eyre::Result<&mut Self> {
-        for command in self.commands.iter() {
-            let mut items = Vec::new();
+    /// the columns after each command, once they've all been validated
+    pub fn replay<C: Crane>(&self, crane: C) -> eyre::Result<Replay<'_, C>> {
+        self.validate()?;
 
-            // pop items into the substack
-            for _ in 0..command.quantity {
-                let char = self
-                    .columns
-                    .get_mut(&command.from)
-                    .ok_or_else(|| eyre::eyre!("unrecognised column {}", &command.from))?
-                    .pop()
-                    .ok_or_else(|| eyre::eyre!("no value to pop from index: {}", &command.from))?;
-                items.push(char);
-            }
+        Ok(Replay {
+            crane,
+            columns: self.columns.clone(),
+            commands: self.commands.iter(),
+        })
+    }
+
+    /// the columns once every command has been applied
+    pub fn run(&self, crane: impl Crane) -> eyre::Result<Columns> {
+        self.validate()?;
 
-            // flip the substack and extend the destination
-            self.columns
-                .get_mut(&command.to)
-                .ok_or_else(|| eyre::eyre!("unrecognised column {}", &command.to))?
-                .extend(items.iter().rev());
+        let mut columns = self.columns.clone();
+        for command in &self.commands {
+            columns.apply(&crane, command);
         }
 
-        Ok(self)
+        Ok(columns)
     }
+}
 
-    /// print the current state of the World
-    pub fn get_output(&self) -> eyre::Result<String> {
-        let mut keys = self.columns.keys().copied().collect::<Vec<_>>();
-        keys.sort_unstable();
+/// an iterator over the intermediate states of a crane's work
+#[derive(Debug)]
+pub struct Replay<'a, C: Crane> {
+    crane: C,
+    columns: Columns,
+    commands: std::slice::Iter<'a, Command>,
+}
 
-        let result = keys
-            .iter()
-            .map(|index| {
-                Ok(self
-                    .columns
-                    .get(index)
-                    .ok_or_else(|| eyre::eyre!("unknown index: {index}"))?
-                    .last()
-                    .ok_or_else(|| eyre::eyre!("column {index} is empty!"))?)
-            })
-            .collect::<eyre::Result<String>>()?;
+impl<C: Crane> Iterator for Replay<'_, C> {
+    type Item = Columns;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let command = self.commands.next()?;
+        self.columns.apply(&self.crane, command);
 
-        Ok(result)
+        Some(self.columns.clone())
     }
 }
 
-pub fn get_input() -> eyre::Result<World> {
-    let input = crate::util::get_input(2022, 5)?;
+/// each command and the columns once the CrateMover 9001 is done with it
+pub fn animation(input: &World) -> eyre::Result<Frames<'_>> {
+    let start = format!("start\n\n{}", input.columns);
+
+    Ok(Box::new(
+        std::iter::once(start).chain(
+            input
+                .commands
+                .iter()
+                .zip(input.replay(CrateMover9001)?)
+                .map(|(command, columns)| format!("{command}\n\n{columns}")),
+        ),
+    ))
+}
 
+/// the drawing of the columns, a blank line, then one command per line
+pub fn parse_world(input: &str) -> eyre::Result<World> {
     // split by the double new line
     let (columns_raw, commands_raw) = input
         .split_once("\n\n")
         .ok_or_else(|| eyre::eyre!("input is poorly formatted"))?;
 
-    // parse columns into a hashmap of vectors
-    let mut columns = Columns::new();
-    for line in columns_raw.lines().rev().skip(1) {
+    // the last line numbers the columns
+    let mut lines = columns_raw.lines().rev();
+    let count = lines
+        .next()
+        .ok_or_else(|| eyre::eyre!("columns are missing"))?
+        .split_whitespace()
+        .count();
+
+    // parse columns bottom up
+    let mut columns = Columns {
+        stacks: vec![Vec::new(); count],
+    };
+    for line in lines {
         for (index, char) in line.chars().skip(1).step_by(4).enumerate() {
             if char != ' ' {
-                columns.entry((index + 1) as _).or_default().push(char);
+                columns
+                    .stacks
+                    .get_mut(index)
+                    .ok_or_else(|| eyre::eyre!("crate `{char}` is outside the columns"))?
+                    .push(char);
             }
         }
     }
@@ -108,15 +232,12 @@ pub fn get_input() -> eyre::Result<World> {
     for line in commands_raw.lines() {
         let parts = line
             .split(' ')
-            .filter_map(|p| match p.parse() {
-                Ok(value) => Some(value),
-                Err(_) => None,
-            })
+            .filter_map(|p| p.parse().ok())
             .collect::<Vec<_>>();
 
         commands.push(Command {
             quantity: *parts
-                .get(0)
+                .first()
                 .ok_or_else(|| eyre::eyre!("unable to build command, missing quantity"))?,
 
             from: *parts
@@ -132,19 +253,105 @@ pub fn get_input() -> eyre::Result<World> {
     Ok(World { columns, commands })
 }
 
-pub fn part_one(input: &mut World) -> eyre::Result<String> {
-    input.cratemover_9000()?.get_output()
+pub fn get_input() -> eyre::Result<World> {
+    parse_world(&crate::util::get_input(2022, 5)?)
 }
 
-pub fn part_two(input: &mut World) -> eyre::Result<String> {
-    input.cratemover_9001()?.get_output()
+pub fn part_one(input: &World) -> eyre::Result<String> {
+    input.run(CrateMover9000)?.tops()
 }
 
-pub fn main() -> eyre::Result<()> {
-    let input = get_input()?;
+pub fn part_two(input: &World) -> eyre::Result<String> {
+    input.run(CrateMover9001)?.tops()
+}
 
-    tracing::info!("Y2022D05P01: {}", part_one(&mut input.clone())?);
-    tracing::info!("Y2022D05P02: {}", part_two(&mut input.clone())?);
+pub fn solution() -> Solution {
+    Solution::new(2022, 5, get_input, part_one, part_two).with_frames(animation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = concat!(
+        "    [D]    \n",
+        "[N] [C]    \n",
+        "[Z] [M] [P]\n",
+        " 1   2   3 \n",
+        "\n",
+        "move 1 from 2 to 1\n",
+        "move 3 from 1 to 3\n",
+        "move 2 from 2 to 1\n",
+        "move 1 from 1 to 2\n",
+    );
+
+    /// the example's columns with different commands
+    fn with_commands(commands: &str) -> World {
+        let (columns, _) = EXAMPLE.split_once("\n\n").unwrap();
+        parse_world(&format!("{columns}\n\n{commands}")).unwrap()
+    }
+
+    #[test]
+    fn example() {
+        let world = parse_world(EXAMPLE).unwrap();
+        assert_eq!(
+            world.columns.stacks,
+            [vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']]
+        );
+        assert_eq!(part_one(&world).unwrap(), "CMZ");
+        assert_eq!(part_two(&world).unwrap(), "MCD");
+    }
+
+    #[test]
+    fn replay_yields_a_state_per_command() {
+        let world = parse_world(EXAMPLE).unwrap();
+        let states = world.replay(CrateMover9000).unwrap().collect::<Vec<_>>();
 
-    Ok(())
+        assert_eq!(states.len(), world.commands.len());
+        assert_eq!(
+            states[0].stacks,
+            [vec!['Z', 'N', 'D'], vec!['M', 'C'], vec!['P']]
+        );
+        assert_eq!(states.last(), Some(&world.run(CrateMover9000).unwrap()));
+
+        // the same moves, but the 9001 keeps the order
+        let states = world.replay(CrateMover9001).unwrap().collect::<Vec<_>>();
+        assert_eq!(
+            states[1].stacks,
+            [vec![], vec!['M', 'C'], vec!['P', 'Z', 'N', 'D']]
+        );
+    }
+
+    #[test]
+    fn bad_commands_are_rejected_up_front() {
+        // the first command is fine, the second names a column that isn't there
+        let world = with_commands("move 1 from 2 to 1\nmove 1 from 1 to 4\n");
+        let error = world.validate().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "command 2 (`move 1 from 1 to 4`): there is no column 4"
+        );
+        assert!(world.replay(CrateMover9000).is_err());
+        assert!(world.run(CrateMover9001).is_err());
+        assert!(animation(&world).is_err());
+
+        // column 3 only has one crate, even after the first command
+        let world = with_commands("move 1 from 1 to 3\nmove 3 from 3 to 2\n");
+        let error = world.validate().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "command 2 (`move 3 from 3 to 2`): column 3 only has 2 crates"
+        );
+        assert!(world.replay(CrateMover9000).is_err());
+
+        assert!(with_commands("move 1 from 0 to 1\n").validate().is_err());
+    }
+
+    #[test]
+    fn incomplete_commands_are_rejected() {
+        let (columns, _) = EXAMPLE.split_once("\n\n").unwrap();
+        assert!(parse_world(&format!("{columns}\n\nmove 1 from 2\n")).is_err());
+        assert!(parse_world(&format!("{columns}\n\nmove 1\n")).is_err());
+        assert!(parse_world(columns).is_err());
+    }
 }
@@ -1,42 +1,147 @@
-use std::collections::HashSet;
+use crate::runner::Solution;
 
-pub fn find_unique_window(input: &[char], window_size: usize) -> u64 {
-    for index in 0..input.len() - window_size {
-        let window = input
-            .get(index..index + window_size)
-            .unwrap() // SAFETY: bounds are enforced above, won't exceed input.len()
-            .iter()
-            .map(ToOwned::to_owned)
-            .collect::<HashSet<_>>();
+/// find the end of the first run of `window_size` distinct bytes, tracking
+/// where each byte was last seen so the window only ever slides forwards
+pub fn find_unique_window(input: &[u8], window_size: usize) -> Option<usize> {
+    if window_size == 0 {
+        return Some(0);
+    }
+
+    let mut last_seen = [None; 256];
+    let mut start = 0;
+
+    for (index, byte) in input.iter().enumerate() {
+        // a repeat inside the window, move the start past it
+        if let Some(previous) = last_seen[*byte as usize] {
+            start = start.max(previous + 1);
+        }
+        last_seen[*byte as usize] = Some(index);
+
+        if index + 1 - start == window_size {
+            return Some(index + 1);
+        }
+    }
+
+    None
+}
+
+/// check every window from scratch, comparing each pair of bytes
+pub fn find_unique_window_naive(input: &[u8], window_size: usize) -> Option<usize> {
+    if window_size == 0 {
+        return Some(0);
+    }
+
+    input
+        .windows(window_size)
+        .position(|w| (0..w.len()).all(|i| !w[i + 1..].contains(&w[i])))
+        .map(|index| index + window_size)
+}
+
+/// slide a mask of the letters in the window, toggling bytes in and out. It's
+/// only all distinct when every byte has its own bit. Only handles `a..=z`,
+/// anything else would share a bit with a letter so gives `None`.
+pub fn find_unique_window_bitmask(input: &[u8], window_size: usize) -> Option<usize> {
+    if !input.iter().all(u8::is_ascii_lowercase) {
+        return None;
+    }
 
-        if window.len() == window_size {
-            return (window_size + index) as _;
+    if window_size == 0 {
+        return Some(0);
+    }
+
+    let bit = |b: u8| 1u32 << (b - b'a');
+    let mut mask = 0;
+
+    for (index, byte) in input.iter().enumerate() {
+        mask ^= bit(*byte);
+        if index >= window_size {
+            mask ^= bit(input[index - window_size]);
+        }
+
+        if mask.count_ones() as usize == window_size {
+            return Some(index + 1);
         }
     }
 
-    0
+    None
+}
+
+pub fn get_input() -> eyre::Result<Vec<u8>> {
+    Ok(crate::util::get_input(2022, 6)?.trim().as_bytes().to_vec())
+}
+
+pub fn part_one(input: &[u8]) -> eyre::Result<usize> {
+    find_unique_window(input, 4).ok_or_else(|| eyre::eyre!("no start-of-packet marker found"))
 }
 
-pub fn get_input() -> eyre::Result<Vec<char>> {
-    Ok(crate::util::get_input(2022, 6)?
-        .trim()
-        .chars()
-        .collect::<Vec<_>>())
+pub fn part_two(input: &[u8]) -> eyre::Result<usize> {
+    find_unique_window(input, 14).ok_or_else(|| eyre::eyre!("no start-of-message marker found"))
 }
 
-pub fn part_one(input: &[char]) -> u64 {
-    find_unique_window(input, 4)
+/// each part with a given search
+fn marker(
+    input: &[u8],
+    window_size: usize,
+    search: fn(&[u8], usize) -> Option<usize>,
+) -> eyre::Result<usize> {
+    search(input, window_size).ok_or_else(|| eyre::eyre!("no {window_size} byte marker found"))
 }
 
-pub fn part_two(input: &[char]) -> u64 {
-    find_unique_window(input, 14)
+pub fn solution() -> Solution {
+    Solution::new(2022, 6, get_input, |i| part_one(i), |i| part_two(i))
+        .with_variant(
+            "naive",
+            |i: &Vec<u8>| marker(i, 4, find_unique_window_naive),
+            |i: &Vec<u8>| marker(i, 14, find_unique_window_naive),
+        )
+        .with_variant(
+            "bitmask",
+            |i: &Vec<u8>| marker(i, 4, find_unique_window_bitmask),
+            |i: &Vec<u8>| marker(i, 14, find_unique_window_bitmask),
+        )
 }
 
-pub fn main() -> eyre::Result<()> {
-    let input = get_input()?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLES: &[(&str, &str, &str)] = &[
+        ("mjqjpqmgbljsphdztnvjfqwrcgsmlb", "7", "19"),
+        ("bvwbjplbgvbhsrlpgdmjqwftvncz", "5", "23"),
+        ("nppdvjthqldpwncqszvftbrmjlhg", "6", "23"),
+        ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", "10", "29"),
+        ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", "11", "26"),
+    ];
+
+    #[test]
+    fn variants_agree_on_examples() {
+        let solution = solution();
+        let names = solution
+            .variants()
+            .iter()
+            .map(|v| v.name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["default", "naive", "bitmask"]);
+
+        for (input, one, two) in EXAMPLES {
+            let input = input.as_bytes().to_vec();
+            for variant in solution.variants() {
+                assert_eq!(variant.part_one(&input).unwrap(), *one, "{}", variant.name);
+                assert_eq!(variant.part_two(&input).unwrap(), *two, "{}", variant.name);
+            }
+        }
+    }
 
-    tracing::info!("Y2022D06P01: {}", part_one(&input));
-    tracing::info!("Y2022D06P02: {}", part_two(&input));
+    #[test]
+    fn bitmask_only_reads_lowercase() {
+        // `A` and `a` would share a bit if it were folded onto the letters
+        let input = b"aAbc";
+        assert_eq!(find_unique_window(input, 4), Some(4));
+        assert_eq!(find_unique_window_naive(input, 4), Some(4));
+        assert_eq!(find_unique_window_bitmask(input, 4), None);
 
-    Ok(())
+        assert_eq!(find_unique_window_bitmask(b"ab{c", 2), None);
+        assert_eq!(find_unique_window_bitmask(b"abcd\n", 4), None);
+        assert_eq!(find_unique_window_bitmask(b"abcd", 4), Some(4));
+    }
 }
@@ -1,4 +1,6 @@
-use std::str::FromStr;
+use std::{collections::BTreeMap, str::FromStr};
+
+use crate::runner::Solution;
 
 #[derive(Debug)]
 pub enum Command {
@@ -46,54 +48,307 @@ impl FromStr for Command {
     }
 }
 
-pub fn get_input() -> eyre::Result<Vec<u64>> {
-    let input = crate::util::get_input(2022, 7)?;
-    let commands = input
-        .lines()
-        .map(FromStr::from_str)
-        .collect::<eyre::Result<Vec<_>>>()?;
+/// a directory in the filesystem, children are indexes into `FileSystem::dirs`
+#[derive(Debug, Default)]
+pub struct Directory {
+    pub name: String,
+    pub parent: Option<usize>,
+    pub dirs: BTreeMap<String, usize>,
+    pub files: BTreeMap<String, u64>,
+}
 
-    let mut result = Vec::new();
-    let mut current = Vec::new();
-
-    for command in commands {
-        match command {
-            Command::ChangeDir(path) => match path.as_ref() {
-                ".." => result.push(current.pop().unwrap()),
-                _ => current.push(0),
-            },
-            Command::ResultFile(_, size) => {
-                for i in &mut current {
-                    *i += size;
+/// an in-memory filesystem, rebuilt from the terminal output
+#[derive(Debug)]
+pub struct FileSystem {
+    // the root directory always lives at index 0
+    pub dirs: Vec<Directory>,
+}
+
+impl Default for FileSystem {
+    fn default() -> Self {
+        Self {
+            dirs: vec![Directory {
+                name: "/".into(),
+                ..Default::default()
+            }],
+        }
+    }
+}
+
+impl FileSystem {
+    pub const ROOT: usize = 0;
+
+    /// replay a terminal session. Directories can be revisited and listed
+    /// more than once, `cd` accepts `/`, `..`, names and absolute paths.
+    pub fn from_commands(commands: &[Command]) -> eyre::Result<Self> {
+        let mut fs = Self::default();
+        let mut cwd = Self::ROOT;
+
+        for command in commands {
+            match command {
+                Command::ChangeDir(path) => cwd = fs.change_dir(cwd, path)?,
+                Command::ResultDir(name) => {
+                    fs.mkdir(cwd, name);
                 }
+                Command::ResultFile(name, size) => {
+                    fs.dirs[cwd].files.insert(name.clone(), *size);
+                }
+
+                // listing doesn't change anything, the results that follow do
+                Command::ListDir => {}
+            }
+        }
+
+        Ok(fs)
+    }
+
+    /// get or create a directory called `name` inside `parent`
+    pub fn mkdir(&mut self, parent: usize, name: &str) -> usize {
+        if let Some(index) = self.dirs[parent].dirs.get(name) {
+            return *index;
+        }
+
+        let index = self.dirs.len();
+        self.dirs.push(Directory {
+            name: name.into(),
+            parent: Some(parent),
+            ..Default::default()
+        });
+        self.dirs[parent].dirs.insert(name.into(), index);
+
+        index
+    }
+
+    /// follow `path` from `cwd`, directories that haven't been listed yet are
+    /// created along the way
+    pub fn change_dir(&mut self, cwd: usize, path: &str) -> eyre::Result<usize> {
+        let mut current = match path.starts_with('/') {
+            true => Self::ROOT,
+            false => cwd,
+        };
+
+        for part in path.split('/').filter(|p| !p.is_empty()) {
+            current = match part {
+                "." => current,
+                ".." => self.dirs[current]
+                    .parent
+                    .ok_or_else(|| eyre::eyre!("can't `cd ..` above the root directory"))?,
+                name => self.mkdir(current, name),
+            };
+        }
+
+        Ok(current)
+    }
+
+    /// find the directory at `path` without creating anything
+    pub fn resolve(&self, path: &str) -> Option<usize> {
+        path.split('/')
+            .filter(|p| !p.is_empty())
+            .try_fold(Self::ROOT, |current, part| match part {
+                "." => Some(current),
+                ".." => self.dirs[current].parent,
+                name => self.dirs[current].dirs.get(name).copied(),
+            })
+    }
+
+    /// total size of a directory, including everything beneath it
+    pub fn size_of(&self, index: usize) -> u64 {
+        let dir = &self.dirs[index];
+
+        dir.files.values().sum::<u64>() + dir.dirs.values().map(|i| self.size_of(*i)).sum::<u64>()
+    }
+
+    /// disk usage of the directory at `path`
+    pub fn du(&self, path: &str) -> eyre::Result<u64> {
+        let index = self
+            .resolve(path)
+            .ok_or_else(|| eyre::eyre!("no such directory: `{path}`"))?;
+
+        Ok(self.size_of(index))
+    }
+
+    /// the size of every directory, in the same order as `dirs`
+    pub fn sizes(&self) -> Vec<u64> {
+        let mut sizes = vec![0; self.dirs.len()];
+
+        // children are always created after their parents, so walking
+        // backwards totals every child before it's added to its parent
+        for index in (0..self.dirs.len()).rev() {
+            sizes[index] += self.dirs[index].files.values().sum::<u64>();
+            if let Some(parent) = self.dirs[index].parent {
+                sizes[parent] += sizes[index];
             }
+        }
+
+        sizes
+    }
+
+    /// the smallest directory that frees up enough space to leave `required`
+    /// available on a disk of `capacity`
+    pub fn deletion_candidate(&self, capacity: u64, required: u64) -> eyre::Result<u64> {
+        let sizes = self.sizes();
+        let used = sizes[Self::ROOT];
+
+        eyre::ensure!(
+            used <= capacity,
+            "{used} used exceeds the disk's {capacity}"
+        );
+        let needed = required.saturating_sub(capacity - used);
+
+        sizes
+            .into_iter()
+            .filter(|size| *size >= needed)
+            .min()
+            .ok_or_else(|| eyre::eyre!("no directory frees up {needed}"))
+    }
 
-            // these other commands are redundant given we assume each file/folder is visited once
-            _ => {}
+    /// render the filesystem like the puzzle description does
+    pub fn tree(&self) -> String {
+        let mut result = String::new();
+        self.render(Self::ROOT, 0, &mut result);
+        result
+    }
+
+    fn render(&self, index: usize, depth: usize, result: &mut String) {
+        let dir = &self.dirs[index];
+        let indent = "  ".repeat(depth);
+
+        result.push_str(&format!("{indent}- {} (dir)\n", dir.name));
+
+        for child in dir.dirs.values() {
+            self.render(*child, depth + 1, result);
+        }
+
+        for (name, size) in &dir.files {
+            result.push_str(&format!("{indent}  - {name} (file, size={size})\n"));
         }
     }
+}
+
+/// rebuild the filesystem from a terminal session, one command or result per line
+pub fn parse_session(input: &str) -> eyre::Result<FileSystem> {
+    let commands = input
+        .lines()
+        .map(FromStr::from_str)
+        .collect::<eyre::Result<Vec<_>>>()?;
+
+    FileSystem::from_commands(&commands)
+}
 
-    // the last dir won't "cd", append it here
-    result.extend(&current);
+pub fn get_input() -> eyre::Result<FileSystem> {
+    parse_session(&crate::util::get_input(2022, 7)?)
+}
 
-    Ok(result)
+pub fn part_one(fs: &FileSystem) -> u64 {
+    fs.sizes().into_iter().filter(|n| *n <= 100000).sum()
 }
 
-pub fn part_one(input: &[u64]) -> u64 {
-    input.iter().filter(|n| **n <= 100000).sum()
+pub fn part_two(fs: &FileSystem) -> eyre::Result<u64> {
+    fs.deletion_candidate(70000000, 30000000)
 }
 
-pub fn part_two(input: &[u64]) -> u64 {
-    let max = input.iter().max().unwrap();
-    let required = 30000000 - (70000000 - max);
-    *input.iter().filter(|n| **n >= required).min().unwrap()
+pub fn solution() -> Solution {
+    Solution::new(2022, 7, get_input, |i| Ok(part_one(i)), part_two)
 }
 
-pub fn main() -> eyre::Result<()> {
-    let input = get_input()?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k
+";
 
-    tracing::info!("Y2022D07P01: {}", part_one(&input));
-    tracing::info!("Y2022D07P02: {}", part_two(&input));
+    #[test]
+    fn example() {
+        let fs = parse_session(EXAMPLE).unwrap();
+        assert_eq!(part_one(&fs), 95437);
+        assert_eq!(part_two(&fs).unwrap(), 24933642);
+    }
+
+    #[test]
+    fn du_totals_everything_beneath() {
+        let fs = parse_session(EXAMPLE).unwrap();
+        assert_eq!(fs.du("/").unwrap(), 48381165);
+        assert_eq!(fs.du("/a").unwrap(), 94853);
+        assert_eq!(fs.du("/a/e").unwrap(), 584);
+        assert_eq!(fs.du("/a/e/../../d").unwrap(), 24933642);
+        assert!(fs.du("/nope").is_err());
+    }
 
-    Ok(())
+    #[test]
+    fn tree_matches_the_puzzle() {
+        let fs = parse_session(EXAMPLE).unwrap();
+        assert_eq!(
+            fs.tree(),
+            "\
+- / (dir)
+  - a (dir)
+    - e (dir)
+      - i (file, size=584)
+    - f (file, size=29116)
+    - g (file, size=2557)
+    - h.lst (file, size=62596)
+  - d (dir)
+    - d.ext (file, size=5626152)
+    - d.log (file, size=8033020)
+    - j (file, size=4060174)
+    - k (file, size=7214296)
+  - b.txt (file, size=14848514)
+  - c.dat (file, size=8504156)
+"
+        );
+    }
+
+    #[test]
+    fn revisiting_and_relisting_count_once() {
+        let session = format!(
+            "{EXAMPLE}$ cd /\n$ ls\ndir a\n14848514 b.txt\n$ cd a\n$ ls\n29116 f\n$ ls\n29116 f\n"
+        );
+        let fs = parse_session(&session).unwrap();
+        let example = parse_session(EXAMPLE).unwrap();
+
+        assert_eq!(fs.dirs.len(), example.dirs.len());
+        assert_eq!(fs.sizes(), example.sizes());
+    }
+
+    #[test]
+    fn cd_follows_absolute_paths() {
+        let fs = parse_session(&format!("{EXAMPLE}$ cd /a/e\n$ ls\n100 z\n")).unwrap();
+        assert_eq!(fs.du("/a/e").unwrap(), 684);
+
+        // an absolute path from deep inside somewhere else
+        let fs = parse_session("$ cd /x/y\n$ cd /a\n$ ls\n5 f\n$ cd /x/y/..\n$ ls\n7 g\n").unwrap();
+        assert_eq!(fs.du("/a").unwrap(), 5);
+        assert_eq!(fs.du("/x").unwrap(), 7);
+        assert_eq!(fs.du("/x/y").unwrap(), 0);
+    }
+
+    #[test]
+    fn cd_above_the_root_is_an_error() {
+        assert!(parse_session("$ cd /\n$ cd ..\n").is_err());
+        assert!(parse_session("$ cd a\n$ cd ../..\n").is_err());
+    }
 }
@@ -1,121 +1,129 @@
-use std::{collections::HashMap, str::FromStr};
+use std::str::FromStr;
 
-use derive_more::{Deref, DerefMut};
+use crate::{
+    runner::Solution,
+    util::image::{gradient, Export, Exports, Image},
+};
 
-// x,y -> height
-#[derive(Debug, Default, Deref, DerefMut)]
+/// a dense, row-major grid of tree heights
+#[derive(Debug, Default)]
 pub struct Trees {
-    #[deref]
-    #[deref_mut]
-    map: HashMap<(i64, i64), i64>,
-
-    // store max bounds
-    pub max_x: i64,
-    pub max_y: i64,
+    pub heights: Vec<i8>,
+    pub width: usize,
+    pub height: usize,
 }
 
 impl FromStr for Trees {
     type Err = eyre::Error;
 
     fn from_str(input: &str) -> eyre::Result<Self> {
-        let mut map = HashMap::new();
-        let mut max_x = 0;
-        let mut max_y = 0;
+        let mut heights = Vec::new();
+        let mut width = 0;
+        let mut height = 0;
 
         for (y, line) in input.lines().enumerate() {
-            max_y = y.max(max_y);
+            // every row must be the same length
+            match y {
+                0 => width = line.len(),
+                _ => eyre::ensure!(
+                    line.len() == width,
+                    "row {y} is {} wide, expected {width}",
+                    line.len()
+                ),
+            }
 
             for (x, char) in line.chars().enumerate() {
-                max_x = x.max(max_x);
-
                 // parse this char into a numerical value, store it at (x,y)
                 let value = char
                     .to_digit(10)
                     .ok_or_else(|| eyre::eyre!("value at ({x},{y}) was not an integer: {char}"))?;
-                map.insert((x as _, y as _), value as _);
+                heights.push(value as _);
             }
+
+            height += 1;
         }
 
         Ok(Self {
-            map,
-            max_x: (max_x + 1) as _,
-            max_y: (max_y + 1) as _,
+            heights,
+            width,
+            height,
         })
     }
 }
 
 impl Trees {
-    pub fn find_visible_trees(&self) -> i64 {
-        // all walls are visible, subtract four for the overlapping corners
-        let mut result = (self.max_x * 2) + (self.max_y * 2) - 4;
-        // let mut result = 0;
-
-        // now check the middle for more visible trees
-        for x in 1..self.max_x - 1 {
-            for y in 1..self.max_y - 1 {
-                // take all rows/columns, remove self, ensure remaining coords are shorter than self
-                let current = self.get(&(x, y)).unwrap();
-
-                // scan north/south/west/east
-                let is_visible = (0..y).all(|j| self.get(&(x, j)).unwrap() < current)
-                    || (y + 1..self.max_y).all(|j| self.get(&(x, j)).unwrap() < current)
-                    || (0..x).all(|i| self.get(&(i, y)).unwrap() < current)
-                    || (x + 1..self.max_x).all(|i| self.get(&(i, y)).unwrap() < current);
-
-                if is_visible {
-                    result += 1;
+    /// the forest from above, taller trees are lighter
+    pub fn image(&self) -> eyre::Result<Image> {
+        Image::from_rows(self.heights.chunks(self.width.max(1)).map(|row| {
+            row.iter()
+                .map(|h| gradient([8, 48, 16], [160, 230, 120], *h as f64 / 9.0))
+        }))
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<i8> {
+        (x < self.width && y < self.height).then(|| self.heights[y * self.width + x])
+    }
+
+    /// every row and column as indexes into `heights`, walked in both directions
+    fn lines(&self) -> Vec<Vec<usize>> {
+        let rows = (0..self.height).map(|y| (0..self.width).map(|x| y * self.width + x).collect());
+        let columns =
+            (0..self.width).map(|x| (0..self.height).map(|y| y * self.width + x).collect());
+
+        rows.chain(columns)
+            .flat_map(|line: Vec<usize>| {
+                let reversed = line.iter().rev().copied().collect();
+                [line, reversed]
+            })
+            .collect()
+    }
+
+    /// sweep each line once, a tree is visible if it's taller than everything before it
+    pub fn find_visible_trees(&self) -> u64 {
+        let mut visible = vec![false; self.heights.len()];
+
+        for line in self.lines() {
+            let mut tallest = -1;
+
+            for index in line {
+                if self.heights[index] > tallest {
+                    visible[index] = true;
+                    tallest = self.heights[index];
                 }
             }
         }
 
-        result
+        visible.iter().filter(|v| **v).count() as _
     }
 
-    pub fn find_most_scenic_tree(&self) -> i64 {
-        let mut result = 0;
+    /// sweep each line once with a monotonic stack, holding the positions of trees
+    /// that could still block the view of trees further along
+    pub fn find_most_scenic_tree(&self) -> u64 {
+        let mut scores = vec![1_u64; self.heights.len()];
 
-        for x in 0..self.max_x {
-            for y in 0..self.max_y {
-                let current = self.get(&(x, y)).unwrap();
+        for line in self.lines() {
+            let mut stack: Vec<(usize, i8)> = Vec::new();
 
-                // for each tree, scan and measure distance until you hit a tree as tall as the current
-                let mut north = 0;
-                for j in (0..y).rev() {
-                    north += 1;
-                    if self.get(&(x, j)).unwrap() >= current {
-                        break;
-                    }
-                }
+            for (position, index) in line.into_iter().enumerate() {
+                let current = self.heights[index];
 
-                let mut south = 0;
-                for j in y + 1..self.max_y {
-                    south += 1;
-                    if self.get(&(x, j)).unwrap() >= current {
-                        break;
-                    }
+                // anything shorter can be seen over, it won't block anything after this
+                while stack.last().is_some_and(|(_, h)| *h < current) {
+                    stack.pop();
                 }
 
-                let mut west = 0;
-                for i in (0..x).rev() {
-                    west += 1;
-                    if self.get(&(i, y)).unwrap() >= current {
-                        break;
-                    }
-                }
-
-                let mut east = 0;
-                for i in x + 1..self.max_x {
-                    east += 1;
-                    if self.get(&(i, y)).unwrap() >= current {
-                        break;
-                    }
-                }
+                // look back to the blocking tree, or the edge of the grid
+                let distance = match stack.last() {
+                    Some((blocker, _)) => position - blocker,
+                    None => position,
+                };
+                scores[index] *= distance as u64;
 
-                result = result.max(north * south * west * east)
+                stack.push((position, current));
             }
         }
 
-        result as _
+        scores.into_iter().max().unwrap_or(0)
     }
 }
 
@@ -125,19 +133,46 @@ pub fn get_input() -> eyre::Result<Trees> {
     FromStr::from_str(&input)
 }
 
-pub fn part_one(trees: &Trees) -> i64 {
+pub fn part_one(trees: &Trees) -> u64 {
     trees.find_visible_trees()
 }
 
-pub fn part_two(trees: &Trees) -> i64 {
+pub fn part_two(trees: &Trees) -> u64 {
     trees.find_most_scenic_tree()
 }
 
-pub fn main() -> eyre::Result<()> {
-    let input = get_input()?;
+/// the forest, scaled up so it's visible
+pub fn export(trees: &Trees) -> eyre::Result<Exports> {
+    Ok(vec![("forest", Export::Still(trees.image()?.scale(4)))])
+}
 
-    tracing::info!("Y2022D08P01: {}", part_one(&input));
-    tracing::info!("Y2022D08P02: {}", part_two(&input));
+pub fn solution() -> Solution {
+    Solution::new(2022, 8, get_input, |i| Ok(part_one(i)), |i| Ok(part_two(i))).with_export(export)
+}
 
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+30373
+25512
+65332
+33549
+35390
+";
+
+    #[test]
+    fn example() {
+        let trees = Trees::from_str(EXAMPLE).unwrap();
+        assert_eq!(trees.find_visible_trees(), 21);
+        assert_eq!(trees.find_most_scenic_tree(), 8);
+    }
+
+    #[test]
+    fn a_single_tree_is_visible() {
+        let trees = Trees::from_str("5\n").unwrap();
+        assert_eq!(trees.find_visible_trees(), 1);
+        assert_eq!(trees.find_most_scenic_tree(), 0);
+    }
 }
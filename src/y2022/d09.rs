@@ -1,11 +1,35 @@
 use std::{collections::HashSet, str::FromStr};
 
-#[derive(Debug)]
+use crate::{runner::Solution, visualize::Frames};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Direction {
     Up,
     Down,
     Right,
     Left,
+
+    // diagonals, for variants where the head can cut corners
+    UpRight,
+    UpLeft,
+    DownRight,
+    DownLeft,
+}
+
+impl Direction {
+    /// how far the head moves in a single step, y points up
+    pub fn delta(&self) -> (i64, i64) {
+        match self {
+            Self::Up => (0, 1),
+            Self::Down => (0, -1),
+            Self::Right => (1, 0),
+            Self::Left => (-1, 0),
+            Self::UpRight => (1, 1),
+            Self::UpLeft => (-1, 1),
+            Self::DownRight => (1, -1),
+            Self::DownLeft => (-1, -1),
+        }
+    }
 }
 
 impl FromStr for Direction {
@@ -17,6 +41,10 @@ impl FromStr for Direction {
             "D" => Ok(Self::Down),
             "R" => Ok(Self::Right),
             "L" => Ok(Self::Left),
+            "UR" => Ok(Self::UpRight),
+            "UL" => Ok(Self::UpLeft),
+            "DR" => Ok(Self::DownRight),
+            "DL" => Ok(Self::DownLeft),
             other => Err(eyre::eyre!("unrecognised direction: `{other}`")),
         }
     }
@@ -24,8 +52,8 @@ impl FromStr for Direction {
 
 #[derive(Debug)]
 pub struct Motion {
-    direction: Direction,
-    steps: i64,
+    pub direction: Direction,
+    pub steps: i64,
 }
 
 impl FromStr for Motion {
@@ -43,7 +71,9 @@ impl FromStr for Motion {
     }
 }
 
-pub fn move_tail(head: (i64, i64), tail: &mut (i64, i64)) {
+pub type Knot = (i64, i64);
+
+pub fn move_tail(head: Knot, tail: &mut Knot) {
     let dx = head.0 - tail.0;
     let dy = head.1 - tail.1;
 
@@ -55,39 +85,145 @@ pub fn move_tail(head: (i64, i64), tail: &mut (i64, i64)) {
     }
 }
 
+/// every knot in a rope, the head first
+#[derive(Debug, Clone)]
+pub struct Rope {
+    pub knots: Vec<Knot>,
+}
+
+impl Rope {
+    /// a rope with every knot at the origin, it always has at least a head
+    pub fn new(knots: usize) -> Self {
+        Self {
+            knots: vec![(0, 0); knots.max(1)],
+        }
+    }
+
+    /// move the head a single step, each knot following the one before it
+    pub fn step(&mut self, direction: Direction) {
+        let (dx, dy) = direction.delta();
+        self.knots[0].0 += dx;
+        self.knots[0].1 += dy;
+
+        // for each segment in the rope, move the tail if the head moves too far
+        for index in 0..self.knots.len() - 1 {
+            let head = self.knots[index];
+            move_tail(head, &mut self.knots[index + 1]);
+        }
+    }
+
+    pub fn tail(&self) -> Knot {
+        // SAFETY: a rope always has a head
+        *self.knots.last().unwrap()
+    }
+}
+
+/// the position of every knot after each step of each motion
+pub fn trace(input: &[Motion], knots: usize) -> impl Iterator<Item = Vec<Knot>> + '_ {
+    let mut rope = Rope::new(knots);
+
+    input
+        .iter()
+        .flat_map(|m| std::iter::repeat_n(m.direction, m.steps.max(0) as usize))
+        .map(move |direction| {
+            rope.step(direction);
+            rope.knots.clone()
+        })
+}
+
+/// how many cells the tail visits, including where it starts
 pub fn simulate_rope(input: &[Motion], knots: usize) -> u64 {
-    let mut rope = vec![(0, 0); knots];
-
-    let mut visited = HashSet::new();
-
-    // for each motion
-    for Motion { direction, steps } in input {
-        // apply the motion, n times
-        (0..*steps).for_each(|_| {
-            // move the head of the rope
-            match direction {
-                Direction::Up => rope[0].1 += 1,
-                Direction::Down => rope[0].1 -= 1,
-                Direction::Right => rope[0].0 += 1,
-                Direction::Left => rope[0].0 -= 1,
-            }
-
-            // for each segment in the rope, move the tail if the head moves too far
-            (0..rope.len() - 1).for_each(|index| move_tail(rope[index], &mut rope[index + 1]));
-
-            // log the tail position after each step of each motion
-            visited.insert(*rope.last().unwrap());
+    let start = Rope::new(knots).tail();
+
+    std::iter::once(start)
+        // SAFETY: a rope always has a head
+        .chain(trace(input, knots).map(|rope| *rope.last().unwrap()))
+        .collect::<HashSet<_>>()
+        .len() as _
+}
+
+/// the smallest area covering every knot throughout a run, including the start
+#[derive(Debug, Clone, Copy)]
+pub struct Bounds {
+    pub min: Knot,
+    pub max: Knot,
+}
+
+impl Bounds {
+    pub fn of(input: &[Motion], knots: usize) -> Self {
+        trace(input, knots).flatten().fold(
+            Self {
+                min: (0, 0),
+                max: (0, 0),
+            },
+            |b, (x, y)| Self {
+                min: (b.min.0.min(x), b.min.1.min(y)),
+                max: (b.max.0.max(x), b.max.1.max(y)),
+            },
+        )
+    }
+}
+
+/// draw a rope the way the puzzle does: `H` for the head, then each knot's
+/// index (`T` for a two knot rope), `s` for the start and `#` for cells the
+/// tail has visited. Knots nearer the head are drawn over later ones.
+pub fn render(rope: &[Knot], visited: &HashSet<Knot>, bounds: Bounds) -> String {
+    let label = |index: usize| match (index, rope.len()) {
+        (0, _) => 'H',
+        (_, 2) => 'T',
+        (i, _) => char::from_digit(i as u32, 10).unwrap_or('+'),
+    };
+
+    // y points up, so draw the rows top down
+    (bounds.min.1..=bounds.max.1)
+        .rev()
+        .map(|y| {
+            (bounds.min.0..=bounds.max.0)
+                .map(|x| match rope.iter().position(|k| *k == (x, y)) {
+                    Some(index) => label(index),
+                    None if (x, y) == (0, 0) => 's',
+                    None if visited.contains(&(x, y)) => '#',
+                    None => '.',
+                })
+                .collect::<String>()
         })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// a frame per step, all drawn over the same area so they can be played back
+pub fn frames(input: &[Motion], knots: usize) -> impl Iterator<Item = String> + '_ {
+    let bounds = Bounds::of(input, knots);
+    let mut visited = HashSet::from([(0, 0)]);
+
+    trace(input, knots).map(move |rope| {
+        // SAFETY: a rope always has a head
+        visited.insert(*rope.last().unwrap());
+        render(&rope, &visited, bounds)
+    })
+}
+
+/// write every frame as plain text, each under a step header
+pub fn dump(input: &[Motion], knots: usize, out: &mut impl std::io::Write) -> eyre::Result<()> {
+    for (step, frame) in frames(input, knots).enumerate() {
+        writeln!(out, "== step {} ==\n{frame}\n", step + 1)?;
     }
 
-    visited.len() as _
+    Ok(())
+}
+
+/// the ten knot rope from part two, step by step
+pub fn animation(input: &[Motion]) -> eyre::Result<Frames<'_>> {
+    Ok(Box::new(frames(input, 10)))
+}
+
+/// one motion per line
+pub fn parse_motions(input: &str) -> eyre::Result<Vec<Motion>> {
+    input.lines().map(FromStr::from_str).collect()
 }
 
 pub fn get_input() -> eyre::Result<Vec<Motion>> {
-    crate::util::get_input(2022, 9)?
-        .lines()
-        .map(FromStr::from_str)
-        .collect::<eyre::Result<Vec<_>>>()
+    parse_motions(&crate::util::get_input(2022, 9)?)
 }
 
 pub fn part_one(input: &[Motion]) -> u64 {
@@ -98,11 +234,103 @@ pub fn part_two(input: &[Motion]) -> u64 {
     simulate_rope(input, 10)
 }
 
-pub fn main() -> eyre::Result<()> {
-    let input = get_input()?;
+pub fn solution() -> Solution {
+    Solution::new(2022, 9, get_input, |i| Ok(part_one(i)), |i| Ok(part_two(i)))
+        .with_frames(|i: &Vec<Motion>| animation(i))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    tracing::info!("Y2022D09P01: {}", part_one(&input));
-    tracing::info!("Y2022D09P02: {}", part_two(&input));
+    const EXAMPLE: &str = "\
+R 4
+U 4
+L 3
+D 1
+R 4
+D 1
+L 5
+R 2
+";
 
-    Ok(())
+    const LARGER_EXAMPLE: &str = "\
+R 5
+U 8
+L 8
+D 3
+R 17
+D 10
+L 25
+U 20
+";
+
+    fn motions(input: &str) -> Vec<Motion> {
+        parse_motions(input).unwrap()
+    }
+
+    #[test]
+    fn example() {
+        let input = motions(EXAMPLE);
+        assert_eq!(part_one(&input), 13);
+        assert_eq!(part_two(&input), 1);
+        assert_eq!(part_two(&motions(LARGER_EXAMPLE)), 36);
+    }
+
+    #[test]
+    fn diagonals_drag_the_tail_along() {
+        let input = motions("UR 3\nDL 1\n");
+        let ropes = trace(&input, 2).collect::<Vec<_>>();
+
+        assert_eq!(
+            ropes,
+            [
+                vec![(1, 1), (0, 0)],
+                vec![(2, 2), (1, 1)],
+                vec![(3, 3), (2, 2)],
+                vec![(2, 2), (2, 2)],
+            ]
+        );
+        assert_eq!(simulate_rope(&input, 2), 3);
+
+        assert_eq!(Direction::DownRight.delta(), (1, -1));
+        assert_eq!("UL".parse::<Direction>().unwrap(), Direction::UpLeft);
+        assert!("X 1".parse::<Motion>().is_err());
+    }
+
+    #[test]
+    fn trace_steps_every_knot() {
+        let input = motions(EXAMPLE);
+        let ropes = trace(&input, 10).collect::<Vec<_>>();
+
+        assert_eq!(ropes.len(), 24);
+        assert!(ropes.iter().all(|rope| rope.len() == 10));
+        assert_eq!(ropes[3][..5], [(4, 0), (3, 0), (2, 0), (1, 0), (0, 0)]);
+    }
+
+    #[test]
+    fn render_labels_knots_like_the_puzzle() {
+        let input = motions("R 4\n");
+        let bounds = Bounds::of(&input, 10);
+        assert_eq!((bounds.min, bounds.max), ((0, 0), (4, 0)));
+
+        // knots nearer the head are drawn on top
+        let rope = trace(&input, 10).last().unwrap();
+        assert_eq!(render(&rope, &HashSet::new(), bounds), "4321H");
+
+        // a two knot rope has a `T`, and the start shows through once left
+        let frames = frames(&input, 2).collect::<Vec<_>>();
+        assert_eq!(frames, ["TH...", "sTH..", "s#TH.", "s##TH"]);
+    }
+
+    #[test]
+    fn dump_writes_every_step() {
+        let mut out = Vec::new();
+        dump(&motions("R 2\n"), 2, &mut out).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "== step 1 ==\nTH.\n\n== step 2 ==\nsTH\n\n"
+        );
+    }
 }
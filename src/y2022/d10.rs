@@ -1,5 +1,14 @@
 use std::{fmt::Display, str::FromStr};
 
+use crate::{
+    runner::Solution,
+    util::{
+        cpu::{self, Cpu, Observer},
+        image::{Colour, Export, Exports, Image, Rgb},
+    },
+    visualize::Frames,
+};
+
 #[derive(Debug, Clone)]
 pub enum Instruction {
     Noop,
@@ -25,6 +34,12 @@ impl Display for Pixel {
     }
 }
 
+impl Colour for Pixel {
+    fn colour(&self) -> Rgb {
+        matches!(self, Pixel::On).colour()
+    }
+}
+
 impl FromStr for Instruction {
     type Err = eyre::Error;
 
@@ -50,136 +65,276 @@ impl FromStr for Instruction {
     }
 }
 
-#[derive(Debug)]
-pub struct ComputerState {
-    pub program_counter: usize,
-    pub cycles: usize,
-    pub register: i64,
-    pub sprite: i64,
-    pub pixels: Vec<Pixel>,
-}
-
-#[derive(Debug)]
-pub struct Computer<C>
-where
-    C: FnMut(&mut ComputerState),
-{
-    // program
-    pub program: Vec<Instruction>,
-
-    // interrupt handler, called on every cpu cycle
-    pub handle_cycle: C,
-
-    // state
-    pub state: ComputerState,
-}
-
-impl<C> Computer<C>
-where
-    C: FnMut(&mut ComputerState),
-{
-    pub fn new(instructions: &[Instruction], handle_cycle: C) -> Self {
-        Self {
-            program: instructions.to_vec(),
-            handle_cycle,
-            state: ComputerState {
-                program_counter: 0,
-                cycles: 0,
-                register: 1,
-                sprite: 1,
-                pixels: Vec::new(),
-            },
+impl cpu::Instruction for Instruction {
+    fn cycles(&self) -> u64 {
+        match self {
+            Self::Noop => 1,
+            Self::AddX(_) => 2,
         }
     }
 
-    pub fn cycle(&mut self) {
-        self.state.cycles += 1;
-        (self.handle_cycle)(&mut self.state)
-    }
-
-    pub fn run(&mut self) -> eyre::Result<()> {
-        loop {
-            // end of program, stop
-            if self.state.program_counter >= self.program.len() {
-                break;
-            }
-
-            // grab instruction, move program counter forward
-            let instruction = self.program[self.state.program_counter].clone();
-            self.state.program_counter += 1;
-
-            // run instruction
-            match instruction {
-                Instruction::Noop => {
-                    self.cycle();
-                }
-
-                Instruction::AddX(value) => {
-                    self.cycle();
-                    self.cycle();
-                    self.state.register += value;
-                    self.state.sprite = self.state.register as _;
-                }
-            };
+    fn execute(&self, state: &mut cpu::State) -> eyre::Result<()> {
+        if let Self::AddX(value) = self {
+            state.registers[X] = state.registers[X]
+                .checked_add(*value)
+                .ok_or_else(|| eyre::eyre!("X overflowed adding {value}"))?;
         }
 
         Ok(())
     }
 }
 
-pub fn get_input() -> eyre::Result<Vec<Instruction>> {
-    crate::util::get_input(2022, 10)?
-        .lines()
-        .map(FromStr::from_str)
-        .collect::<eyre::Result<Vec<_>>>()
+/// the only register, it also positions the sprite
+pub const X: usize = 0;
+
+/// a cpu loaded with the program, X starts at 1
+pub fn computer(program: &[Instruction]) -> Cpu<Instruction> {
+    let mut cpu = Cpu::new(program.to_vec(), &["x"]);
+    cpu.state.registers[X] = 1;
+    cpu
 }
 
-pub fn part_one(input: &[Instruction]) -> eyre::Result<u64> {
-    let mut result = 0;
-    let mut computer = Computer::new(input, |state| {
-        if state.cycles >= 20 && (state.cycles as u64 - 20).rem_euclid(40) == 0 {
-            result += state.cycles as u64 * state.register as u64;
+/// sums the signal strength during the 20th cycle and every 40th after
+#[derive(Debug, Default)]
+pub struct SignalStrength {
+    pub total: i64,
+}
+
+impl Observer for SignalStrength {
+    fn on_cycle(&mut self, state: &cpu::State) {
+        if state.cycle >= 20 && (state.cycle - 20).is_multiple_of(40) {
+            self.total += state.cycle as i64 * state.registers[X];
         }
-    });
+    }
+}
 
-    computer.run()?;
+/// draws a pixel every cycle, lit if the sprite covers it
+#[derive(Debug, Default)]
+pub struct Crt {
+    pub rows: Vec<Vec<Pixel>>,
+    pub current: Vec<Pixel>,
+}
 
-    Ok(result)
+impl Crt {
+    /// the screen so far, with the sprite drawn under the row being scanned
+    pub fn render(&self, sprite: i64) -> String {
+        let draw = |row: &[Pixel]| row.iter().map(ToString::to_string).collect::<String>();
+        let sprite = (0..40)
+            .map(|x| match (sprite - 1..=sprite + 1).contains(&x) {
+                true => '=',
+                false => ' ',
+            })
+            .collect::<String>();
+
+        self.rows
+            .iter()
+            .map(|row| draw(row))
+            .chain([draw(&self.current), sprite])
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// the screen so far, pixels yet to be drawn are black. It's padded to at
+    /// least the six rows of a full screen, so every frame is the same size.
+    pub fn image(&self) -> eyre::Result<Image> {
+        let blank = [0, 0, 0];
+        let mut rows = self
+            .rows
+            .iter()
+            .map(|row| row.iter().map(Pixel::colour).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        if !self.current.is_empty() {
+            rows.push(
+                self.current
+                    .iter()
+                    .map(Pixel::colour)
+                    .chain(std::iter::repeat(blank))
+                    .take(40)
+                    .collect(),
+            );
+        }
+        rows.resize(rows.len().max(6), vec![blank; 40]);
+
+        Image::from_rows(rows)
+    }
 }
 
-pub fn part_two(input: &[Instruction]) -> eyre::Result<String> {
-    let mut result = Vec::new();
-    let mut computer = Computer::new(input, |state| {
-        let pixel = match (state.sprite - 1..state.sprite + 2).contains(&(state.pixels.len() as _))
-        {
+impl Observer for Crt {
+    fn on_cycle(&mut self, state: &cpu::State) {
+        let sprite = state.registers[X];
+        let pixel = match (sprite - 1..=sprite + 1).contains(&(self.current.len() as _)) {
             true => Pixel::On,
             false => Pixel::Off,
         };
-        state.pixels.push(pixel);
-
-        if state.cycles.rem_euclid(40) == 0 {
-            let row = state
-                .pixels
-                .iter()
-                .map(ToString::to_string)
-                .collect::<String>();
-            result.push(row);
-            state.pixels.clear();
+        self.current.push(pixel);
+
+        if self.current.len() == 40 {
+            self.rows.push(std::mem::take(&mut self.current));
         }
-    });
-    computer.run()?;
+    }
+}
+
+/// the CRT once the program is done
+pub fn screen_image(input: &[Instruction]) -> eyre::Result<Image> {
+    let mut crt = Crt::default();
+    computer(input).run(&mut crt)?;
+
+    crt.image()
+}
+
+/// the CRT as each cycle draws another pixel
+pub fn screen_frames(input: &[Instruction]) -> eyre::Result<Vec<Image>> {
+    let mut crt = Crt::default();
+    let mut frames = Vec::new();
+
+    computer(input).run(&mut |state: &cpu::State| {
+        crt.on_cycle(state);
+        frames.push(crt.image());
+    })?;
+
+    frames.into_iter().collect()
+}
 
-    // lol, there's probably a more graceful way of doing this.
-    Ok(result
-        .join("\n                                                                         ")
-        .into())
+/// the finished screen, and the beam drawing it
+pub fn export(input: &[Instruction]) -> eyre::Result<Exports> {
+    let frames = screen_frames(input)?.iter().map(|f| f.scale(8)).collect();
+
+    Ok(vec![
+        ("screen", Export::Still(screen_image(input)?.scale(8))),
+        ("beam", Export::Animated(frames, 4)),
+    ])
+}
+
+/// the CRT beam drawing the screen, a frame per cycle
+pub fn animation(input: &[Instruction]) -> eyre::Result<Frames<'_>> {
+    let mut crt = Crt::default();
+    let mut frames = Vec::new();
+
+    computer(input).run(&mut |state: &cpu::State| {
+        crt.on_cycle(state);
+        frames.push(crt.render(state.registers[X]));
+    })?;
+
+    Ok(Box::new(frames.into_iter()))
 }
 
-pub fn main() -> eyre::Result<()> {
-    let input = get_input()?;
+/// one instruction per line
+pub fn parse_program(input: &str) -> eyre::Result<Vec<Instruction>> {
+    input.lines().map(FromStr::from_str).collect()
+}
 
-    tracing::info!("Y2022D10P01: {}", part_one(&input)?);
-    tracing::info!("Y2022D10P02: {}", part_two(&input)?);
+pub fn get_input() -> eyre::Result<Vec<Instruction>> {
+    parse_program(&crate::util::get_input(2022, 10)?)
+}
 
-    Ok(())
+pub fn part_one(input: &[Instruction]) -> eyre::Result<i64> {
+    let mut signal = SignalStrength::default();
+    computer(input).run(&mut signal)?;
+
+    Ok(signal.total)
+}
+
+pub fn part_two(input: &[Instruction]) -> eyre::Result<String> {
+    let mut crt = Crt::default();
+    computer(input).run(&mut crt)?;
+    let rows = crt.rows;
+
+    // read the letters off the CRT
+    let lit = rows
+        .iter()
+        .map(|row| row.iter().map(|p| matches!(p, Pixel::On)).collect())
+        .collect::<Vec<_>>();
+
+    if let Some(letters) = crate::util::ocr::recognise(&lit) {
+        return Ok(letters);
+    }
+
+    // unknown glyphs, fall back to the raw CRT on its own lines
+    tracing::warn!("unable to recognise the CRT output, returning the raw grid");
+    Ok(rows
+        .iter()
+        .map(|row| row.iter().map(ToString::to_string).collect::<String>())
+        .fold(String::new(), |acc, row| acc + "\n" + &row))
+}
+
+pub fn solution() -> Solution {
+    Solution::new(2022, 10, get_input, |i| part_one(i), |i| part_two(i))
+        .with_frames(|i: &Vec<Instruction>| animation(i))
+        .with_export(|i: &Vec<Instruction>| export(i))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("testdata/10.txt");
+
+    const SCREEN: &str = "\
+##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######.....";
+
+    fn draw(rows: &[Vec<Pixel>]) -> String {
+        rows.iter()
+            .map(|row| {
+                row.iter()
+                    .map(|p| match p {
+                        Pixel::On => '#',
+                        Pixel::Off => '.',
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn example() {
+        let program = parse_program(EXAMPLE).unwrap();
+        assert_eq!(part_one(&program).unwrap(), 13140);
+
+        let mut crt = Crt::default();
+        computer(&program).run(&mut crt).unwrap();
+        assert_eq!(draw(&crt.rows), SCREEN);
+        assert!(crt.current.is_empty());
+
+        // not letters, so part two falls back to the raw screen
+        let raw = part_two(&program).unwrap();
+        assert_eq!(raw.replace(' ', "."), format!("\n{SCREEN}"));
+    }
+
+    #[test]
+    fn signal_strength_samples_during_the_cycle() {
+        // X is still 1 during cycle 20, the addx only lands at its end
+        let mut program = parse_program(&"noop\n".repeat(18)).unwrap();
+        program.push(Instruction::AddX(5));
+        program.push(Instruction::Noop);
+
+        let mut signal = SignalStrength::default();
+        computer(&program).run(&mut signal).unwrap();
+        assert_eq!(signal.total, 20);
+    }
+
+    #[test]
+    fn images_are_a_full_screen() {
+        let program = parse_program(EXAMPLE).unwrap();
+        let frames = screen_frames(&program).unwrap();
+
+        assert_eq!(frames.len(), 240);
+        assert!(frames.iter().all(|f| (f.width, f.height) == (40, 6)));
+        assert_eq!(frames.last(), Some(&screen_image(&program).unwrap()));
+    }
+
+    #[test]
+    fn overflows_name_the_cycle() {
+        let program = parse_program(&format!("addx {}\naddx 1\n", i64::MAX)).unwrap();
+        let error = part_one(&program).unwrap_err();
+
+        assert_eq!(error.to_string(), "cycle 2, address 0");
+        assert!(error.root_cause().to_string().contains("X overflowed"));
+    }
 }
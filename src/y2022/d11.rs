@@ -1,22 +1,143 @@
-use std::collections::VecDeque;
+use std::{collections::VecDeque, fmt::Display, str::FromStr};
 
-#[derive(Debug, Clone)]
-pub enum MonkeyOperation {
-    // these are the only operations that appear to be used
-    Add(i64),
-    Multiply(i64),
-    Square,
+use eyre::Context;
+
+use crate::{
+    runner::Solution,
+    util::{
+        cycle,
+        image::{Export, Exports},
+        top::top_k,
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+impl Operator {
+    /// apply the operator, erroring on overflow or division by zero
+    pub fn apply(self, left: i64, right: i64) -> eyre::Result<i64> {
+        eyre::ensure!(
+            !(self == Self::Divide && right == 0),
+            "`{left} / 0` divides by zero"
+        );
+
+        let result = match self {
+            Self::Add => left.checked_add(right),
+            Self::Subtract => left.checked_sub(right),
+            Self::Multiply => left.checked_mul(right),
+            Self::Divide => left.checked_div(right),
+        };
+
+        result.ok_or_else(|| eyre::eyre!("`{left} {self} {right}` overflows"))
+    }
 }
 
-impl TryFrom<(&str, &str)> for MonkeyOperation {
+impl TryFrom<&str> for Operator {
     type Error = eyre::Error;
 
-    fn try_from(value: (&str, &str)) -> eyre::Result<Self> {
+    fn try_from(value: &str) -> eyre::Result<Self> {
         match value {
-            ("*", "old") => Ok(Self::Square),
-            ("*", v) => Ok(Self::Multiply(v.parse()?)),
-            ("+", v) => Ok(Self::Add(v.parse()?)),
-            (op, _) => Err(eyre::eyre!("unknown operator: {op}")),
+            "+" => Ok(Self::Add),
+            "-" => Ok(Self::Subtract),
+            "*" => Ok(Self::Multiply),
+            "/" => Ok(Self::Divide),
+            op => Err(eyre::eyre!("unknown operator: {op}")),
+        }
+    }
+}
+
+impl Display for Operator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Add => write!(f, "+"),
+            Self::Subtract => write!(f, "-"),
+            Self::Multiply => write!(f, "*"),
+            Self::Divide => write!(f, "/"),
+        }
+    }
+}
+
+/// the right hand side of an `Operation: new = ...` line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expression {
+    Old,
+    Literal(i64),
+    Binary(Box<Expression>, Operator, Box<Expression>),
+}
+
+type Tokens<'a> = std::iter::Peekable<std::str::SplitWhitespace<'a>>;
+
+impl Expression {
+    /// evaluate against the current worry level
+    pub fn evaluate(&self, old: i64) -> eyre::Result<i64> {
+        match self {
+            Self::Old => Ok(old),
+            Self::Literal(v) => Ok(*v),
+            Self::Binary(left, op, right) => op.apply(left.evaluate(old)?, right.evaluate(old)?),
+        }
+    }
+
+    // `+` and `-` bind loosest...
+    fn parse_sum(tokens: &mut Tokens) -> eyre::Result<Self> {
+        let mut left = Self::parse_product(tokens)?;
+        while let Some(op) = tokens.next_if(|t| matches!(*t, "+" | "-")) {
+            let right = Self::parse_product(tokens)?;
+            left = Self::Binary(Box::new(left), op.try_into()?, Box::new(right));
+        }
+        Ok(left)
+    }
+
+    // ...then `*` and `/`, both left associative
+    fn parse_product(tokens: &mut Tokens) -> eyre::Result<Self> {
+        let mut left = Self::parse_term(tokens)?;
+        while let Some(op) = tokens.next_if(|t| matches!(*t, "*" | "/")) {
+            let right = Self::parse_term(tokens)?;
+            eyre::ensure!(
+                !(op == "/" && right == Self::Literal(0)),
+                "operation divides by zero"
+            );
+            left = Self::Binary(Box::new(left), op.try_into()?, Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_term(tokens: &mut Tokens) -> eyre::Result<Self> {
+        match tokens.next() {
+            Some("old") => Ok(Self::Old),
+            Some(t) => Ok(Self::Literal(t.parse().map_err(|_| {
+                eyre::eyre!("expected `old` or a number, found `{t}`")
+            })?)),
+            None => Err(eyre::eyre!("operation ended early")),
+        }
+    }
+}
+
+impl FromStr for Expression {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> eyre::Result<Self> {
+        let mut tokens = s.split_whitespace().peekable();
+        let expression = Self::parse_sum(&mut tokens)?;
+
+        match tokens.next() {
+            None => Ok(expression),
+            Some(t) => Err(eyre::eyre!("unexpected `{t}` in operation `{s}`")),
+        }
+    }
+}
+
+impl Display for Expression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Old => write!(f, "old"),
+            Self::Literal(v) => write!(f, "{v}"),
+            Self::Binary(left, op, right) => write!(f, "{left} {op} {right}"),
         }
     }
 }
@@ -24,7 +145,7 @@ impl TryFrom<(&str, &str)> for MonkeyOperation {
 #[derive(Debug, Default)]
 pub struct MonkeyBuilder {
     items: Option<VecDeque<i64>>,
-    operation: Option<MonkeyOperation>,
+    operation: Option<Expression>,
     test_condition: Option<i64>,
     test_true: Option<usize>,
     test_false: Option<usize>,
@@ -36,7 +157,7 @@ impl MonkeyBuilder {
         self
     }
 
-    pub fn set_operation(&mut self, operation: MonkeyOperation) -> &mut Self {
+    pub fn set_operation(&mut self, operation: Expression) -> &mut Self {
         self.operation = Some(operation);
         self
     }
@@ -56,22 +177,37 @@ impl MonkeyBuilder {
         self
     }
 
-    pub fn build(self) -> eyre::Result<Monkey> {
+    /// build the monkey, `monkeys` is how many there are in total so the
+    /// throw targets can be checked
+    pub fn build(self, monkeys: usize) -> eyre::Result<Monkey> {
+        let test_condition = self
+            .test_condition
+            .ok_or_else(|| eyre::eyre!("test_condition was not set"))?;
+        eyre::ensure!(test_condition != 0, "test_condition can't be zero");
+
+        let test_true = self
+            .test_true
+            .ok_or_else(|| eyre::eyre!("test_true was not set"))?;
+        let test_false = self
+            .test_false
+            .ok_or_else(|| eyre::eyre!("test_false was not set"))?;
+
+        for target in [test_true, test_false] {
+            eyre::ensure!(
+                target < monkeys,
+                "throws to monkey {target}, but there are only {monkeys} monkeys"
+            );
+        }
+
         Ok(Monkey {
             inspections: 0,
             items: self.items.ok_or_else(|| eyre::eyre!("items was not set"))?,
             operation: self
                 .operation
                 .ok_or_else(|| eyre::eyre!("operation was not set"))?,
-            test_condition: self
-                .test_condition
-                .ok_or_else(|| eyre::eyre!("test_condition was not set"))?,
-            test_true: self
-                .test_true
-                .ok_or_else(|| eyre::eyre!("test_true was not set"))?,
-            test_false: self
-                .test_false
-                .ok_or_else(|| eyre::eyre!("test_false was not set"))?,
+            test_condition,
+            test_true,
+            test_false,
         })
     }
 }
@@ -82,7 +218,7 @@ pub struct Monkey {
     pub items: VecDeque<i64>,
 
     // what operation to perform
-    pub operation: MonkeyOperation,
+    pub operation: Expression,
 
     // if divisible by condition...
     pub test_condition: i64,
@@ -92,10 +228,34 @@ pub struct Monkey {
     pub test_false: usize,
 }
 
-pub fn get_input() -> eyre::Result<Vec<Monkey>> {
-    Ok(crate::util::get_input(2022, 11)?
-        .split("\n\n")
-        .map(|monkey| {
+/// how worry levels are kept in check after each inspection
+#[derive(Debug, Clone, Copy)]
+pub enum Relief {
+    // worry is divided after each inspection
+    Divide(i64),
+
+    // worry is wrapped around a modulus that preserves every monkey's test
+    Modulo(i64),
+}
+
+impl Relief {
+    /// wrap worry around the lcm of every monkey's divisor, so each test still
+    /// gives the same answer without the values growing forever
+    pub fn common_modulus(monkeys: &[Monkey]) -> eyre::Result<Self> {
+        crate::util::math::lcm_all(monkeys.iter().map(|m| m.test_condition))
+            .map(Self::Modulo)
+            .ok_or_else(|| eyre::eyre!("the lcm of every monkey's divisor overflowed"))
+    }
+}
+
+/// every monkey's notes, one paragraph each
+pub fn parse_monkeys(input: &str) -> eyre::Result<Vec<Monkey>> {
+    let monkeys = input.trim().split("\n\n").collect::<Vec<_>>();
+
+    monkeys
+        .iter()
+        .enumerate()
+        .map(|(index, monkey)| {
             let mut builder = MonkeyBuilder::default();
 
             for line in monkey.lines().skip(1).map(|l| l.trim().to_lowercase()) {
@@ -110,8 +270,13 @@ pub fn get_input() -> eyre::Result<Vec<Monkey>> {
                                 .collect::<eyre::Result<VecDeque<_>>>()?,
                         );
                     }
-                    ["operation:", "new", "=", "old", operator, value] => {
-                        builder.set_operation(MonkeyOperation::try_from((operator, value))?);
+                    ["operation:", "new", "=", ..] => {
+                        builder.set_operation(
+                            line.split_once("= ")
+                                .ok_or_else(|| eyre::eyre!("operation line is poorly formatted"))?
+                                .1
+                                .parse()?,
+                        );
                     }
                     ["test:", "divisible", "by", value] => {
                         builder.set_test_condition(value.parse()?);
@@ -126,75 +291,558 @@ pub fn get_input() -> eyre::Result<Vec<Monkey>> {
                 };
             }
 
-            Ok(builder.build()?)
+            builder
+                .build(monkeys.len())
+                .wrap_err_with(|| format!("monkey {index}"))
         })
-        .collect::<eyre::Result<Vec<_>>>()?)
+        .collect()
 }
 
-pub fn monkey_business(input: &[Monkey], rounds: u64, worry_factor: i64) -> u64 {
-    let mut monkeys = input.to_vec();
+pub fn get_input() -> eyre::Result<Vec<Monkey>> {
+    parse_monkeys(&crate::util::get_input(2022, 11)?)
+}
 
-    for _ in 0..rounds {
-        for i in 0..monkeys.len() {
-            for _ in 0..monkeys[i].items.len() {
-                // SAFETY: bounds check above.
-                let mut item = monkeys[i].items.pop_front().unwrap();
-
-                // increase inspections
-                monkeys[i].inspections += 1;
-
-                // check each item, apply the operation to their worry factor
-                item = match &monkeys[i].operation {
-                    MonkeyOperation::Add(v) => item + v,
-                    MonkeyOperation::Multiply(v) => item * v,
-                    MonkeyOperation::Square => item * item,
-                };
+/// play a single round, every monkey inspecting and throwing each of its items
+pub fn play_round(monkeys: &mut [Monkey], relief: Relief) -> eyre::Result<()> {
+    play_round_with(monkeys, relief, |_, _, _| {})
+}
 
-                // before testing, adjust the item's worry value
-                item = match &worry_factor == &0 {
-                    true => item % 9699690, // wat
-                    false => item / &worry_factor,
-                };
+/// play a single round, calling `on_throw(from, to, worry)` for every throw.
+/// Monkeys always inspect from the front and catch at the back, so the
+/// throws are enough to follow an item around.
+pub fn play_round_with(
+    monkeys: &mut [Monkey],
+    relief: Relief,
+    mut on_throw: impl FnMut(usize, usize, i64),
+) -> eyre::Result<()> {
+    for i in 0..monkeys.len() {
+        for _ in 0..monkeys[i].items.len() {
+            // SAFETY: bounds check above.
+            let item = monkeys[i].items.pop_front().unwrap();
 
-                // where should the item be thrown?
-                match item % monkeys[i].test_condition == 0 {
-                    true => {
-                        let index = monkeys[i].test_true;
-                        monkeys[index].items.push_back(item);
-                    }
-                    false => {
-                        let index = monkeys[i].test_false;
-                        monkeys[index].items.push_back(item);
-                    }
-                };
+            // increase inspections
+            monkeys[i].inspections += 1;
+
+            // check each item, apply the operation to their worry factor
+            let operation = &monkeys[i].operation;
+            let worry = operation
+                .evaluate(item)
+                .wrap_err_with(|| format!("monkey {i} applying `{operation}` to item {item}"))?;
+
+            // before testing, adjust the item's worry value
+            let item = match relief {
+                Relief::Divide(v) => worry / v,
+                Relief::Modulo(v) => worry % v,
+            };
+
+            // where should the item be thrown?
+            let index = match item % monkeys[i].test_condition == 0 {
+                true => monkeys[i].test_true,
+                false => monkeys[i].test_false,
+            };
+            monkeys[index].items.push_back(item);
+            on_throw(i, index, item);
+        }
+    }
+
+    Ok(())
+}
+
+/// what every monkey did during a single round
+#[derive(Debug, Clone)]
+pub struct RoundStats {
+    pub round: u64,
+
+    // inspections made during this round, per monkey
+    pub inspections: Vec<u64>,
+
+    // items held by each monkey once the round is over
+    pub held: Vec<usize>,
+}
+
+/// a single throw of an item, with its worry level once it lands
+#[derive(Debug, Clone, Copy)]
+pub struct Hop {
+    pub round: u64,
+    pub from: usize,
+    pub to: usize,
+    pub worry: i64,
+}
+
+/// an instrumented run, items are numbered by where they start, monkey by
+/// monkey in the order they're held
+#[derive(Debug, Clone)]
+pub struct Trace {
+    pub monkeys: Vec<Monkey>,
+    pub rounds: Vec<RoundStats>,
+    pub starts: Vec<(usize, i64)>,
+    pub paths: Vec<Vec<Hop>>,
+}
+
+impl Trace {
+    /// run the simulation, recording every round and throw
+    pub fn record(input: &[Monkey], rounds: u64, relief: Relief) -> eyre::Result<Self> {
+        let mut monkeys = input.to_vec();
+
+        // number every item, and keep track of who holds which
+        let starts = monkeys
+            .iter()
+            .enumerate()
+            .flat_map(|(i, m)| m.items.iter().map(move |item| (i, *item)))
+            .collect::<Vec<_>>();
+
+        let mut held = vec![VecDeque::new(); monkeys.len()];
+        for (id, (monkey, _)) in starts.iter().enumerate() {
+            held[*monkey].push_back(id);
+        }
+
+        let mut paths = vec![Vec::new(); starts.len()];
+        let mut stats = Vec::new();
+
+        for round in 1..=rounds {
+            let before = monkeys.iter().map(|m| m.inspections).collect::<Vec<_>>();
+
+            play_round_with(&mut monkeys, relief, |from, to, worry| {
+                // SAFETY: every thrown item was caught by `from` earlier
+                let id = held[from].pop_front().unwrap();
+                held[to].push_back(id);
+                paths[id].push(Hop {
+                    round,
+                    from,
+                    to,
+                    worry,
+                });
+            })?;
+
+            stats.push(RoundStats {
+                round,
+                inspections: monkeys
+                    .iter()
+                    .zip(&before)
+                    .map(|(m, b)| m.inspections - b)
+                    .collect(),
+                held: monkeys.iter().map(|m| m.items.len()).collect(),
+            });
+        }
+
+        Ok(Self {
+            monkeys,
+            rounds: stats,
+            starts,
+            paths,
+        })
+    }
+
+    /// the monkeys an item visited, starting with the one that first held it
+    pub fn route(&self, item: usize) -> Option<Vec<usize>> {
+        let (start, _) = self.starts.get(item)?;
+
+        Some(
+            std::iter::once(*start)
+                .chain(self.paths[item].iter().map(|h| h.to))
+                .collect(),
+        )
+    }
+
+    /// `round,monkey,inspections,held`, one line per monkey per round
+    pub fn rounds_csv(&self) -> String {
+        let mut csv = String::from("round,monkey,inspections,held\n");
+
+        for stats in &self.rounds {
+            for (monkey, (inspections, held)) in
+                stats.inspections.iter().zip(&stats.held).enumerate()
+            {
+                csv.push_str(&format!("{},{monkey},{inspections},{held}\n", stats.round));
             }
         }
+
+        csv
+    }
+
+    /// `item,round,from,to,worry`, one line per throw
+    pub fn paths_csv(&self) -> String {
+        let mut csv = String::from("item,round,from,to,worry\n");
+
+        for (item, path) in self.paths.iter().enumerate() {
+            for hop in path {
+                csv.push_str(&format!(
+                    "{item},{},{},{},{}\n",
+                    hop.round, hop.from, hop.to, hop.worry
+                ));
+            }
+        }
+
+        csv
+    }
+
+    /// the whole trace as pretty-printed json
+    pub fn to_json(&self) -> eyre::Result<String> {
+        let rounds = self
+            .rounds
+            .iter()
+            .map(|r| {
+                serde_json::json!({
+                    "round": r.round,
+                    "inspections": r.inspections,
+                    "held": r.held,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let items = self
+            .starts
+            .iter()
+            .zip(&self.paths)
+            .map(|((monkey, worry), path)| {
+                serde_json::json!({
+                    "start": { "monkey": monkey, "worry": worry },
+                    "hops": path
+                        .iter()
+                        .map(|h| serde_json::json!({
+                            "round": h.round,
+                            "from": h.from,
+                            "to": h.to,
+                            "worry": h.worry,
+                        }))
+                        .collect::<Vec<_>>(),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        Ok(serde_json::to_string_pretty(&serde_json::json!({
+            "inspections": self.monkeys.iter().map(|m| m.inspections).collect::<Vec<_>>(),
+            "rounds": rounds,
+            "items": items,
+        }))?)
+    }
+}
+
+/// where every item is and how worried it is, which is all that decides how
+/// the following rounds play out
+fn positions(monkeys: &[Monkey]) -> Vec<(usize, i64)> {
+    let mut items = monkeys
+        .iter()
+        .enumerate()
+        .flat_map(|(i, m)| m.items.iter().map(move |worry| (i, *worry)))
+        .collect::<Vec<_>>();
+    items.sort_unstable();
+    items
+}
+
+/// each monkey's inspections after `rounds` rounds. If the items ever end up
+/// back where they were, every loop after adds the same inspections, so the
+/// rest is extrapolated rather than played.
+pub fn inspections_after(
+    input: &[Monkey],
+    rounds: usize,
+    relief: Relief,
+) -> eyre::Result<Vec<u64>> {
+    let counts = |monkeys: &[Monkey]| monkeys.iter().map(|m| m.inspections).collect::<Vec<_>>();
+    let mut history = vec![counts(input)];
+    let mut error = None;
+
+    let found = cycle::detect(
+        input.to_vec(),
+        rounds,
+        |monkeys| {
+            let mut monkeys = monkeys.clone();
+            if error.is_none() {
+                if let Err(e) = play_round(&mut monkeys, relief) {
+                    error = Some(e);
+                }
+            }
+            history.push(counts(&monkeys));
+            monkeys
+        },
+        |monkeys| positions(monkeys),
+    );
+
+    if let Some(e) = error {
+        return Err(e);
+    }
+
+    match found {
+        // no repeat, so detect played every round
+        None => Ok(history[rounds].clone()),
+        Some(found) => (0..input.len())
+            .map(|i| {
+                let column = history.iter().map(|counts| counts[i]).collect::<Vec<_>>();
+                found
+                    .extrapolate(&column, rounds)
+                    .ok_or_else(|| eyre::eyre!("unable to extrapolate monkey {i}'s inspections"))
+            })
+            .collect(),
+    }
+}
+
+pub fn monkey_business(input: &[Monkey], rounds: u64, relief: Relief) -> eyre::Result<u64> {
+    let mut monkeys = input.to_vec();
+
+    for _ in 0..rounds {
+        play_round(&mut monkeys, relief)?;
     }
 
     // get the top two active
-    monkeys.sort_unstable_by_key(|m| m.inspections);
+    Ok(top_k(monkeys.iter().map(|m| m.inspections), 2)
+        .iter()
+        .product())
+}
 
-    monkeys
+/// as `monkey_business`, skipping ahead once the items start repeating
+pub fn monkey_business_by_cycle(
+    input: &[Monkey],
+    rounds: usize,
+    relief: Relief,
+) -> eyre::Result<u64> {
+    Ok(top_k(inspections_after(input, rounds, relief)?, 2)
         .iter()
-        .map(|m| m.inspections)
-        .rev()
-        .take(2)
-        .product()
+        .product())
 }
 
-pub fn part_one(input: &[Monkey]) -> u64 {
-    monkey_business(input, 20, 3.into())
+pub fn part_one(input: &[Monkey]) -> eyre::Result<u64> {
+    monkey_business(input, 20, Relief::Divide(3))
 }
 
-pub fn part_two(input: &[Monkey]) -> u64 {
-    monkey_business(input, 10000, 0.into())
+pub fn part_two(input: &[Monkey]) -> eyre::Result<u64> {
+    monkey_business(input, 10000, Relief::common_modulus(input)?)
 }
 
-pub fn main() -> eyre::Result<()> {
-    let input = get_input()?;
+/// a trace of part one's rounds, as csv and json
+pub fn export(input: &[Monkey]) -> eyre::Result<Exports> {
+    let trace = Trace::record(input, 20, Relief::Divide(3))?;
 
-    tracing::info!("Y2022D11P01: {}", part_one(&input));
-    tracing::info!("Y2022D11P02: {}", part_two(&input));
+    Ok(vec![
+        ("rounds", Export::Text(trace.rounds_csv(), "csv")),
+        ("paths", Export::Text(trace.paths_csv(), "csv")),
+        ("trace", Export::Text(trace.to_json()?, "json")),
+    ])
+}
 
-    Ok(())
+pub fn solution() -> Solution {
+    Solution::new(2022, 11, get_input, |i| part_one(i), |i| part_two(i))
+        .with_variant(
+            "cycle",
+            |i: &Vec<Monkey>| monkey_business_by_cycle(i, 20, Relief::Divide(3)),
+            |i: &Vec<Monkey>| monkey_business_by_cycle(i, 10000, Relief::common_modulus(i)?),
+        )
+        .with_export(|i: &Vec<Monkey>| export(i))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 2
+    If false: throw to monkey 3
+
+Monkey 1:
+  Starting items: 54, 65, 75, 74
+  Operation: new = old + 6
+  Test: divisible by 19
+    If true: throw to monkey 2
+    If false: throw to monkey 0
+
+Monkey 2:
+  Starting items: 79, 60, 97
+  Operation: new = old * old
+  Test: divisible by 13
+    If true: throw to monkey 1
+    If false: throw to monkey 3
+
+Monkey 3:
+  Starting items: 74
+  Operation: new = old + 3
+  Test: divisible by 17
+    If true: throw to monkey 0
+    If false: throw to monkey 1
+";
+
+    #[test]
+    fn cycles_extrapolate_like_playing_every_round() {
+        let monkeys = parse_monkeys(EXAMPLE).unwrap();
+
+        // a small modulus, so the items repeat quickly
+        let relief = Relief::Modulo(23);
+        let mut played = monkeys.clone();
+        for _ in 0..5000 {
+            play_round(&mut played, relief).unwrap();
+        }
+
+        let expected = played.iter().map(|m| m.inspections).collect::<Vec<_>>();
+        assert_eq!(inspections_after(&monkeys, 5000, relief).unwrap(), expected);
+    }
+
+    #[test]
+    fn cycles_stop_at_the_last_round() {
+        // the second round overflows, so only the first can be played
+        let monkeys = parse_monkeys(
+            "\
+Monkey 0:
+  Starting items: 1
+  Operation: new = old * 1000000000
+  Test: divisible by 2
+    If true: throw to monkey 1
+    If false: throw to monkey 1
+
+Monkey 1:
+  Starting items: 1
+  Operation: new = old * 1000000000
+  Test: divisible by 2
+    If true: throw to monkey 0
+    If false: throw to monkey 0
+",
+        )
+        .unwrap();
+
+        let relief = Relief::Divide(1);
+        assert_eq!(monkey_business(&monkeys, 1, relief).unwrap(), 2);
+        assert_eq!(monkey_business_by_cycle(&monkeys, 1, relief).unwrap(), 2);
+        assert!(monkey_business(&monkeys, 2, relief).is_err());
+        assert!(monkey_business_by_cycle(&monkeys, 2, relief).is_err());
+    }
+
+    #[test]
+    fn trace_adds_up() {
+        let monkeys = parse_monkeys(EXAMPLE).unwrap();
+        let trace = Trace::record(&monkeys, 20, Relief::Divide(3)).unwrap();
+        assert_eq!(trace.rounds.len(), 20);
+
+        // every round's inspections sum to each monkey's total
+        let totals = (0..monkeys.len())
+            .map(|i| trace.rounds.iter().map(|r| r.inspections[i]).sum::<u64>())
+            .collect::<Vec<_>>();
+        let counted = trace
+            .monkeys
+            .iter()
+            .map(|m| m.inspections)
+            .collect::<Vec<_>>();
+        assert_eq!(totals, [101, 95, 7, 105]);
+        assert_eq!(totals, counted);
+
+        // nothing is dropped or duplicated
+        for round in &trace.rounds {
+            assert_eq!(round.held.iter().sum::<usize>(), 10);
+        }
+    }
+
+    #[test]
+    fn trace_follows_an_item() {
+        let monkeys = parse_monkeys(EXAMPLE).unwrap();
+        let trace = Trace::record(&monkeys, 2, Relief::Divide(3)).unwrap();
+
+        // 79 goes 0 -> 3 -> 1 in the first round, then 57 is divisible by 19
+        assert_eq!(trace.route(0).unwrap(), [0, 3, 1, 2, 3, 1]);
+        let worries = trace.paths[0].iter().map(|h| h.worry).collect::<Vec<_>>();
+        assert_eq!(worries, [500, 167, 57, 1083, 362]);
+        assert_eq!(trace.route(10), None);
+
+        let csv = trace.paths_csv();
+        assert!(csv.starts_with("item,round,from,to,worry\n0,1,0,3,500\n"));
+        assert!(trace
+            .rounds_csv()
+            .starts_with("round,monkey,inspections,held\n"));
+        assert!(trace.to_json().is_ok());
+    }
+
+    #[test]
+    fn common_modulus_overflow_is_an_error() {
+        let mut monkeys = parse_monkeys(EXAMPLE).unwrap();
+        for (monkey, divisor) in monkeys
+            .iter_mut()
+            .zip([1_000_000_007, 998_244_353, 1_000_000_009])
+        {
+            monkey.test_condition = divisor;
+        }
+
+        assert!(Relief::common_modulus(&monkeys).is_err());
+        assert!(part_two(&monkeys).is_err());
+    }
+
+    fn expression(s: &str) -> Expression {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn operations_follow_precedence() {
+        assert_eq!(expression("old + old").evaluate(7).unwrap(), 14);
+        assert_eq!(expression("old * 2 + 3").evaluate(5).unwrap(), 13);
+        assert_eq!(expression("3 + old * 2").evaluate(5).unwrap(), 13);
+        assert_eq!(expression("old - 2 - 3").evaluate(10).unwrap(), 5);
+        assert_eq!(expression("old / 2 * 3").evaluate(9).unwrap(), 12);
+        assert_eq!(expression("old * old").evaluate(9).unwrap(), 81);
+
+        // `*` groups tighter, so this is `old + (old * 2)`
+        assert_eq!(
+            expression("old + old * 2"),
+            Expression::Binary(
+                Box::new(Expression::Old),
+                Operator::Add,
+                Box::new(Expression::Binary(
+                    Box::new(Expression::Old),
+                    Operator::Multiply,
+                    Box::new(Expression::Literal(2)),
+                )),
+            )
+        );
+        assert_eq!(expression("old * 2 + 3").to_string(), "old * 2 + 3");
+    }
+
+    #[test]
+    fn bad_operations_are_rejected() {
+        for bad in ["", "old +", "old ^ 2", "old old", "new * 2", "old / 0"] {
+            assert!(bad.parse::<Expression>().is_err(), "`{bad}`");
+        }
+
+        let error = "old / 0".parse::<Expression>().unwrap_err();
+        assert_eq!(error.to_string(), "operation divides by zero");
+    }
+
+    #[test]
+    fn division_by_zero_is_not_an_overflow() {
+        // only zero once the worry is, so parsing can't catch it
+        let error = expression("old / old").evaluate(0).unwrap_err();
+        assert_eq!(error.to_string(), "`0 / 0` divides by zero");
+
+        let error = expression("old * old").evaluate(i64::MAX).unwrap_err();
+        assert!(error.to_string().ends_with("overflows"), "{error}");
+    }
+
+    #[test]
+    fn overflows_name_the_monkey_and_item() {
+        let mut monkeys = parse_monkeys(EXAMPLE).unwrap();
+        monkeys[1].items = VecDeque::from([i64::MAX - 1]);
+
+        let error = play_round(&mut monkeys, Relief::Divide(3)).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!("monkey 1 applying `old + 6` to item {}", i64::MAX - 1)
+        );
+        assert!(error.root_cause().to_string().ends_with("overflows"));
+    }
+
+    #[test]
+    fn errors_name_the_monkey() {
+        let broken = EXAMPLE.replace("throw to monkey 3", "throw to monkey 9");
+        let error = parse_monkeys(&broken).unwrap_err();
+
+        assert_eq!(error.to_string(), "monkey 0");
+        assert!(error.chain().count() > 1);
+    }
+
+    #[test]
+    fn example() {
+        let monkeys = parse_monkeys(EXAMPLE).unwrap();
+        assert_eq!(part_one(&monkeys).unwrap(), 10605);
+        assert_eq!(part_two(&monkeys).unwrap(), 2713310158);
+
+        let cycle = solution();
+        let cycle = cycle.variant("cycle").unwrap();
+        assert_eq!(cycle.part_one(&monkeys).unwrap(), "10605");
+        assert_eq!(cycle.part_two(&monkeys).unwrap(), "2713310158");
+    }
 }
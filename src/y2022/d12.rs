@@ -1,5 +1,13 @@
+use std::collections::VecDeque;
+
 use pathfinding::matrix::Matrix;
 
+use crate::{
+    runner::Solution,
+    util::image::{gradient, Colour, Export, Exports, Image, Rgb},
+    visualize::Frames,
+};
+
 pub type Pos = (usize, usize);
 
 #[derive(Debug, Clone)]
@@ -32,6 +40,26 @@ impl Tile {
     }
 }
 
+impl Colour for Tile {
+    fn colour(&self) -> Rgb {
+        match self {
+            Tile::Start => [220, 40, 40],
+            Tile::End => [40, 80, 220],
+            Tile::Elevation(h) => gradient([20, 60, 20], [240, 240, 230], (*h - 1) as f64 / 25.0),
+        }
+    }
+}
+
+/// the heightmap with a route drawn over it
+pub fn route_image(input: &Matrix<Tile>, path: &[Pos]) -> eyre::Result<Image> {
+    let mut image = Image::from_rows(input.iter().map(|row| row.iter().map(Tile::colour)))?;
+    for (row, column) in path {
+        image.pixels[row * input.columns + column] = [250, 160, 20];
+    }
+
+    Ok(image)
+}
+
 /// returns two pairs, the Start and the End
 pub fn find_start_and_end(input: &Matrix<Tile>) -> (Pos, Pos) {
     let mut start = (0, 0);
@@ -48,80 +76,264 @@ pub fn find_start_and_end(input: &Matrix<Tile>) -> (Pos, Pos) {
     (start, end)
 }
 
-pub fn get_successors(input: &Matrix<Tile>, pos: Pos) -> Vec<(Pos, u64)> {
-    input
-        .neighbours(pos, false)
-        .filter_map(|neighbour| {
-            // SAFETY: these are bounds checked
-            let a = input.get(pos).unwrap();
-            let b = input.get(neighbour).unwrap();
+/// can you climb from `a` to `b`? Up one step at most, but down as far as you like
+pub fn can_climb(a: &Tile, b: &Tile) -> bool {
+    b.height() - a.height() <= 1
+}
 
-            // change in height must be no greater than 1, but can descend as much as you like
-            (b.height() - a.height() <= 1).then_some((neighbour, 1))
-        })
+/// every cell's shortest route to the End, found with a single BFS walking
+/// backwards from it
+#[derive(Debug)]
+pub struct Routes {
+    pub end: Pos,
+
+    // how many steps each cell is from the End, None if it can't get there
+    pub distance: Matrix<Option<u64>>,
+
+    // the next cell to step to on the way to the End
+    pub next: Matrix<Option<Pos>>,
+}
+
+impl Routes {
+    pub fn from_end(input: &Matrix<Tile>, end: Pos) -> Self {
+        let mut distance = Matrix::new(input.rows, input.columns, None);
+        let mut next = Matrix::new(input.rows, input.columns, None);
+        let mut queue = VecDeque::from([end]);
+        distance[end] = Some(0);
+
+        while let Some(pos) = queue.pop_front() {
+            let steps = distance[pos].unwrap_or(0) + 1;
+
+            // walking backwards, so check the neighbour could climb to here
+            for neighbour in input.neighbours(pos, false) {
+                if distance[neighbour].is_none() && can_climb(&input[neighbour], &input[pos]) {
+                    distance[neighbour] = Some(steps);
+                    next[neighbour] = Some(pos);
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+
+        Self {
+            end,
+            distance,
+            next,
+        }
+    }
+
+    /// how many steps it takes to get from `start` to the End
+    pub fn cost(&self, start: Pos) -> Option<u64> {
+        self.distance.get(start).copied().flatten()
+    }
+
+    /// every cell visited on the way from `start` to the End, inclusive
+    pub fn path(&self, start: Pos) -> Option<Vec<Pos>> {
+        self.cost(start)?;
+
+        let mut result = vec![start];
+        while let Some(pos) = self.next[*result.last()?] {
+            result.push(pos);
+        }
+
+        Some(result)
+    }
+
+    /// the closest cell to the End matching `predicate`, and how far away it is
+    pub fn closest(
+        &self,
+        input: &Matrix<Tile>,
+        predicate: impl Fn(&Tile) -> bool,
+    ) -> Option<(Pos, u64)> {
+        input
+            .items()
+            .filter(|(_, tile)| predicate(tile))
+            .filter_map(|(pos, _)| Some((pos, self.cost(pos)?)))
+            .min_by_key(|(_, cost)| *cost)
+    }
+}
+
+/// the heightmap, with the Start and End found. Each part runs its own
+/// search, so parsing is only parsing.
+#[derive(Debug)]
+pub struct Hill {
+    pub tiles: Matrix<Tile>,
+    pub start: Pos,
+    pub end: Pos,
+}
+
+impl Hill {
+    pub fn new(tiles: Matrix<Tile>) -> Self {
+        let (start, end) = find_start_and_end(&tiles);
+
+        Self { tiles, start, end }
+    }
+
+    /// every cell's shortest route to the End
+    pub fn routes(&self) -> Routes {
+        Routes::from_end(&self.tiles, self.end)
+    }
+}
+
+/// the map as the puzzle draws it
+fn canvas(input: &Matrix<Tile>) -> Matrix<char> {
+    let mut canvas = Matrix::new(input.rows, input.columns, ' ');
+    for (pos, tile) in input.items() {
+        canvas[pos] = match tile {
+            Tile::Start => 'S',
+            Tile::End => 'E',
+            Tile::Elevation(h) => (b'a' + *h as u8 - 1) as char,
+        };
+    }
+
+    canvas
+}
+
+fn to_string(canvas: &Matrix<char>) -> String {
+    canvas
+        .iter()
+        .map(|row| row.iter().collect::<String>())
         .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// draw a route over the heightmap, each step pointing to the next
+pub fn render_route(input: &Matrix<Tile>, path: &[Pos]) -> String {
+    let mut canvas = canvas(input);
+
+    for pair in path.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        canvas[a] = match (b.0 as i64 - a.0 as i64, b.1 as i64 - a.1 as i64) {
+            (-1, _) => '^',
+            (1, _) => 'v',
+            (_, -1) => '<',
+            _ => '>',
+        };
+    }
+
+    to_string(&canvas)
 }
 
-pub fn manhattan_distance(a: &Pos, b: &Pos) -> u64 {
-    (a.0.abs_diff(b.0) + a.1.abs_diff(b.1)) as _
+/// the search once it's `depth` steps out from the End: `#` is the
+/// frontier and `.` is already explored
+pub fn render_search(input: &Matrix<Tile>, routes: &Routes, depth: u64) -> String {
+    let mut canvas = canvas(input);
+    for (pos, distance) in routes.distance.items() {
+        match distance {
+            Some(d) if *d == depth => canvas[pos] = '#',
+            Some(d) if *d < depth => canvas[pos] = '.',
+            _ => {}
+        }
+    }
+
+    to_string(&canvas)
 }
 
-pub fn shortest_route(input: &Matrix<Tile>, start: &Pos, end: &Pos) -> Option<u64> {
-    let result = pathfinding::directed::astar::astar(
-        start,
-        |pos| get_successors(input, *pos),
-        |pos| manhattan_distance(pos, end),
-        |pos| pos == end,
-    );
+/// the search spreading out from the End, then the route from the Start
+pub fn animation(hill: &Hill) -> eyre::Result<Frames<'_>> {
+    let routes = hill.routes();
+    let furthest = routes
+        .distance
+        .values()
+        .flatten()
+        .max()
+        .copied()
+        .unwrap_or(0);
+    let route = render_route(&hill.tiles, &routes.path(hill.start).unwrap_or_default());
 
-    // return the cost if one was found
-    result.map(|(_, cost)| cost)
+    Ok(Box::new(
+        (0..=furthest)
+            .map(move |depth| render_search(&hill.tiles, &routes, depth))
+            .chain(std::iter::once(route)),
+    ))
 }
 
-pub fn get_input() -> eyre::Result<Matrix<Tile>> {
-    let input = crate::util::get_input(2022, 12)?;
+/// the heightmap with the route from the Start
+pub fn export(hill: &Hill) -> eyre::Result<Exports> {
+    let path = hill
+        .routes()
+        .path(hill.start)
+        .ok_or_else(|| eyre::eyre!("Failed to find a route"))?;
+
+    Ok(vec![(
+        "route",
+        Export::Still(route_image(&hill.tiles, &path)?.scale(4)),
+    )])
+}
 
+/// the heightmap, one row per line
+pub fn parse_hill(input: &str) -> eyre::Result<Hill> {
     let rows = input
         .lines()
         .map(|line| {
-            Ok(line
-                .chars()
-                .map(|c| Tile::try_from(c))
-                .collect::<eyre::Result<Vec<_>>>()?)
+            line.chars()
+                .map(Tile::try_from)
+                .collect::<eyre::Result<Vec<_>>>()
         })
         .collect::<eyre::Result<Vec<_>>>()?;
 
-    Ok(Matrix::from_rows(rows)?)
+    Ok(Hill::new(Matrix::from_rows(rows)?))
 }
 
-pub fn part_one(input: &Matrix<Tile>) -> eyre::Result<u64> {
-    let (start, end) = find_start_and_end(input);
-    let cost =
-        shortest_route(input, &start, &end).ok_or_else(|| eyre::eyre!("Failed to find a route"))?;
-
-    Ok(cost)
+pub fn get_input() -> eyre::Result<Hill> {
+    parse_hill(&crate::util::get_input(2022, 12)?)
 }
 
-pub fn part_two(input: &Matrix<Tile>) -> eyre::Result<u64> {
-    let (_, end) = find_start_and_end(input);
+pub fn part_one(hill: &Hill) -> eyre::Result<u64> {
+    hill.routes()
+        .cost(hill.start)
+        .ok_or_else(|| eyre::eyre!("Failed to find a route"))
+}
 
-    let cost = input
-        .items()
-        .filter_map(|(start, tile)| match tile {
-            Tile::Start | Tile::Elevation(1) => shortest_route(input, &start, &end),
-            _ => None,
-        })
-        .min()
+pub fn part_two(hill: &Hill) -> eyre::Result<u64> {
+    let (_, cost) = hill
+        .routes()
+        .closest(&hill.tiles, |tile| tile.height() == 1)
         .ok_or_else(|| eyre::eyre!("Failed to find a route"))?;
 
     Ok(cost)
 }
 
-pub fn main() -> eyre::Result<()> {
-    let input = get_input()?;
+pub fn solution() -> Solution {
+    Solution::new(2022, 12, get_input, part_one, part_two)
+        .with_frames(animation)
+        .with_export(export)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Sabqponm
+abcryxxl
+accszExk
+acctuvwj
+abdefghi
+";
+
+    #[test]
+    fn example() {
+        let hill = parse_hill(EXAMPLE).unwrap();
+        assert_eq!(part_one(&hill).unwrap(), 31);
+        assert_eq!(part_two(&hill).unwrap(), 29);
+    }
+
+    #[test]
+    fn routes_walk_to_the_end() {
+        let hill = parse_hill(EXAMPLE).unwrap();
+        assert_eq!((hill.start, hill.end), ((0, 0), (2, 5)));
 
-    tracing::info!("Y2022D12P01: {}", part_one(&input)?);
-    tracing::info!("Y2022D12P02: {}", part_two(&input)?);
+        let routes = hill.routes();
+        let path = routes.path(hill.start).unwrap();
+        assert_eq!(path.len(), 32);
+        assert_eq!(path.last(), Some(&hill.end));
+        assert_eq!(routes.cost(hill.end), Some(0));
 
-    Ok(())
+        // every step is one cell and climbable
+        for pair in path.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            assert_eq!(a.0.abs_diff(b.0) + a.1.abs_diff(b.1), 1);
+            assert!(can_climb(&hill.tiles[a], &hill.tiles[b]));
+        }
+    }
 }
@@ -1,89 +1,281 @@
-use itertools::Itertools;
+use std::{cmp::Ordering, fmt::Display, str::FromStr};
 
-use serde_json::{json, Value};
+use crate::runner::Solution;
 
-pub fn get_input() -> eyre::Result<Vec<Value>> {
-    let input = crate::util::get_input(2022, 13)?;
+#[derive(Debug, Clone, Eq)]
+pub enum Packet {
+    Int(u64),
+    List(Vec<Packet>),
+}
 
-    Ok(input
-        .trim()
-        .lines()
-        .filter(|line| !line.is_empty())
-        .map(|line| Ok(serde_json::from_str(line)?))
-        .collect::<eyre::Result<Vec<_>>>()?)
+impl Ord for Packet {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Packet::Int(a), Packet::Int(b)) => a.cmp(b),
+
+            // slices compare element by element, the shorter one first if they run out
+            (Packet::List(a), Packet::List(b)) => a.cmp(b),
+
+            // mixed types, promote the integer to a list of one
+            (Packet::Int(_), Packet::List(b)) => std::slice::from_ref(self).cmp(b.as_slice()),
+            (Packet::List(a), Packet::Int(_)) => a.as_slice().cmp(std::slice::from_ref(other)),
+        }
+    }
+}
+
+// equal exactly when neither orders before the other, so `[2]` == `[[2]]`
+impl PartialEq for Packet {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
 }
 
-pub fn cmp(left: &Value, right: &Value) -> i64 {
-    match (left, right) {
-        (Value::Number(a), Value::Number(b)) => {
-            // SAFETY: the above clause verifies the value is a numeric type
-            let x = a.as_i64().unwrap();
-            let y = b.as_i64().unwrap();
-
-            match x.cmp(&y) {
-                std::cmp::Ordering::Less => -1,
-                std::cmp::Ordering::Equal => 0,
-                std::cmp::Ordering::Greater => 1,
+impl PartialOrd for Packet {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for Packet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Packet::Int(v) => write!(f, "{v}"),
+            Packet::List(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                write!(f, "]")
             }
         }
-        (Value::Array(a), Value::Array(b)) => {
-            for pair in a.iter().zip_longest(b.iter()) {
-                let result = match pair {
-                    itertools::EitherOrBoth::Left(_) => 1,
-                    itertools::EitherOrBoth::Right(_) => -1,
-                    itertools::EitherOrBoth::Both(x, y) => cmp(x, y),
-                };
-
-                if result != 0 {
-                    return result;
-                }
+    }
+}
+
+/// a recursive descent parser over a single line, tracking the column so
+/// errors can point at the problem
+struct Parser<'a> {
+    line: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(line: &'a str) -> Self {
+        Self {
+            line,
+            bytes: line.as_bytes(),
+            pos: 0,
+        }
+    }
+
+    fn error(&self, expected: &str) -> eyre::Error {
+        let found = match self.bytes.get(self.pos) {
+            Some(b) => format!("`{}`", *b as char),
+            None => "end of line".into(),
+        };
+
+        eyre::eyre!(
+            "expected {expected} but found {found} at column {} of `{}`",
+            self.pos + 1,
+            self.line
+        )
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn packet(&mut self) -> eyre::Result<Packet> {
+        match self.peek() {
+            Some(b'[') => self.list(),
+            Some(b'0'..=b'9') => self.int(),
+            _ => Err(self.error("`[` or a digit")),
+        }
+    }
+
+    fn int(&mut self) -> eyre::Result<Packet> {
+        let start = self.pos;
+        while matches!(self.peek(), Some(b'0'..=b'9')) {
+            self.pos += 1;
+        }
+
+        match self.line[start..self.pos].parse() {
+            Ok(v) => Ok(Packet::Int(v)),
+            Err(_) => {
+                // point at the start of the number, not the end
+                self.pos = start;
+                Err(self.error("a number that fits in a u64"))
             }
+        }
+    }
 
-            0
+    fn list(&mut self) -> eyre::Result<Packet> {
+        // skip the opening bracket
+        self.pos += 1;
+
+        let mut items = Vec::new();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(Packet::List(items));
         }
-        (Value::Number(_), Value::Array(_)) => {
-            let x = Value::Array(vec![left.clone()]);
-            cmp(&x, right)
+
+        loop {
+            items.push(self.packet()?);
+
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(Packet::List(items));
+                }
+                _ => return Err(self.error("`,` or `]`")),
+            }
         }
-        (Value::Array(_), Value::Number(_)) => {
-            let y = Value::Array(vec![right.clone()]);
-            cmp(left, &y)
+    }
+}
+
+impl FromStr for Packet {
+    type Err = eyre::Error;
+
+    fn from_str(line: &str) -> eyre::Result<Self> {
+        let mut parser = Parser::new(line.trim());
+        let packet = parser.packet()?;
+
+        // make sure nothing trails the packet
+        match parser.peek() {
+            None => Ok(packet),
+            Some(_) => Err(parser.error("end of line")),
         }
-        (_, _) => return 0, // you shouldn't get here...
     }
 }
 
-pub fn part_one(input: &[Value]) -> i64 {
-    (0..input.len())
-        .tuples()
-        .filter_map(|(i, j)| (cmp(&input[i], &input[j]) == -1).then_some((i as i64 + 1) / 2 + 1))
+/// pairs of packets, one per line, separated by a blank line
+pub fn parse_pairs(input: &str) -> eyre::Result<Vec<(Packet, Packet)>> {
+    input
+        .trim()
+        .split("\n\n")
+        .map(|pair| {
+            let (left, right) = pair
+                .split_once('\n')
+                .ok_or_else(|| eyre::eyre!("packet pair is missing a line: `{pair}`"))?;
+
+            Ok((left.parse()?, right.parse()?))
+        })
+        .collect()
+}
+
+pub fn get_input() -> eyre::Result<Vec<(Packet, Packet)>> {
+    parse_pairs(&crate::util::get_input(2022, 13)?)
+}
+
+pub fn part_one(input: &[(Packet, Packet)]) -> u64 {
+    input
+        .iter()
+        .enumerate()
+        .filter(|(_, (left, right))| left < right)
+        .map(|(index, _)| index as u64 + 1)
         .sum()
 }
 
-pub fn part_two(input: &[Value]) -> i64 {
-    let div2 = json!([[2]]);
-    let div6 = json!([[6]]);
+pub fn part_two(input: &[(Packet, Packet)]) -> eyre::Result<u64> {
+    let dividers: [Packet; 2] = ["[[2]]".parse()?, "[[6]]".parse()?];
 
-    let input_sorted = input
+    let mut packets = input
         .iter()
-        .chain([&div2, &div6])
-        .map(ToOwned::to_owned)
-        .sorted_unstable_by(|a, b| cmp(a, b).cmp(&0))
+        .flat_map(|(left, right)| [left, right])
+        .chain(&dividers)
         .collect::<Vec<_>>();
+    packets.sort_unstable();
 
-    // SAFETY: these elements are confirmed to exist, they were inserted earlier
-    let x = input_sorted.iter().position(|i| i == &div2).unwrap();
-    let y = input_sorted.iter().position(|i| i == &div6).unwrap();
+    // non-zero indexing, and packets equal to a divider may sort either side
+    // of it, so only count the ones strictly before
+    Ok(dividers
+        .iter()
+        .map(|divider| packets.partition_point(|p| *p < divider) as u64 + 1)
+        .product())
+}
 
-    // non-zero indexing...
-    ((x + 1) * (y + 1)) as _
+pub fn solution() -> Solution {
+    Solution::new(2022, 13, get_input, |i| Ok(part_one(i)), |i| part_two(i))
 }
 
-pub fn main() -> eyre::Result<()> {
-    let input = get_input()?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+[1,1,3,1,1]
+[1,1,5,1,1]
 
-    tracing::info!("Y2022D13P01: {}", part_one(&input));
-    tracing::info!("Y2022D13P02: {}", part_two(&input));
+[[1],[2,3,4]]
+[[1],4]
 
-    Ok(())
+[9]
+[[8,7,6]]
+
+[[4,4],4,4]
+[[4,4],4,4,4]
+
+[7,7,7,7]
+[7,7,7]
+
+[]
+[3]
+
+[[[]]]
+[[]]
+
+[1,[2,[3,[4,[5,6,7]]]],8,9]
+[1,[2,[3,[4,[5,6,0]]]],8,9]
+";
+
+    fn packet(s: &str) -> Packet {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn integers_promote_to_lists() {
+        assert_eq!(packet("[2]"), packet("[[2]]"));
+        assert_eq!(packet("2"), packet("[[[2]]]"));
+        assert_eq!(packet("[1,[2]]"), packet("[[1],2]"));
+        assert_ne!(packet("[2]"), packet("[2,2]"));
+        assert_ne!(packet("[]"), packet("[[]]"));
+    }
+
+    #[test]
+    fn shorter_lists_come_first() {
+        assert!(packet("[]") < packet("[[]]"));
+        assert!(packet("[[]]") < packet("[[[]]]"));
+        assert!(packet("[1,1]") < packet("[1,1,0]"));
+        assert!(packet("[2]") > packet("[1,9,9]"));
+    }
+
+    #[test]
+    fn example() {
+        let pairs = parse_pairs(EXAMPLE).unwrap();
+        assert_eq!(part_one(&pairs), 13);
+        assert_eq!(part_two(&pairs).unwrap(), 140);
+    }
+
+    #[test]
+    fn errors_point_at_the_column() {
+        let error = "[1,99999999999999999999]".parse::<Packet>().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "expected a number that fits in a u64 but found `9` at column 4 of `[1,99999999999999999999]`"
+        );
+
+        let error = "[1;2]".parse::<Packet>().unwrap_err();
+        assert!(error.to_string().contains("column 3"), "{error}");
+    }
+
+    #[test]
+    fn dividers_count_only_packets_strictly_before() {
+        let input = vec![("[2]".parse().unwrap(), "[[6]]".parse().unwrap())];
+        // `[[2]]` is first, `[[6]]` is after `[2]` and `[[2]]`, whatever the sort left
+        assert_eq!(part_two(&input).unwrap(), 3);
+    }
 }
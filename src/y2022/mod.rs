@@ -1,3 +1,5 @@
+use crate::runner::Solution;
+
 #[cfg(feature = "y2022d01")]
 pub mod d01;
 
@@ -37,45 +39,33 @@ pub mod d12;
 #[cfg(feature = "y2022d13")]
 pub mod d13;
 
-pub fn main() -> eyre::Result<()> {
-    #[cfg(feature = "y2022d01")]
-    d01::main()?;
-
-    #[cfg(feature = "y2022d02")]
-    d02::main()?;
-
-    #[cfg(feature = "y2022d03")]
-    d03::main()?;
-
-    #[cfg(feature = "y2022d04")]
-    d04::main()?;
-
-    #[cfg(feature = "y2022d05")]
-    d05::main()?;
-
-    #[cfg(feature = "y2022d06")]
-    d06::main()?;
-
-    #[cfg(feature = "y2022d07")]
-    d07::main()?;
-
-    #[cfg(feature = "y2022d08")]
-    d08::main()?;
-
-    #[cfg(feature = "y2022d09")]
-    d09::main()?;
-
-    #[cfg(feature = "y2022d10")]
-    d10::main()?;
-
-    #[cfg(feature = "y2022d11")]
-    d11::main()?;
-
-    #[cfg(feature = "y2022d12")]
-    d12::main()?;
-
-    #[cfg(feature = "y2022d13")]
-    d13::main()?;
-
-    Ok(())
+pub fn solutions() -> Vec<Solution> {
+    vec![
+        #[cfg(feature = "y2022d01")]
+        d01::solution(),
+        #[cfg(feature = "y2022d02")]
+        d02::solution(),
+        #[cfg(feature = "y2022d03")]
+        d03::solution(),
+        #[cfg(feature = "y2022d04")]
+        d04::solution(),
+        #[cfg(feature = "y2022d05")]
+        d05::solution(),
+        #[cfg(feature = "y2022d06")]
+        d06::solution(),
+        #[cfg(feature = "y2022d07")]
+        d07::solution(),
+        #[cfg(feature = "y2022d08")]
+        d08::solution(),
+        #[cfg(feature = "y2022d09")]
+        d09::solution(),
+        #[cfg(feature = "y2022d10")]
+        d10::solution(),
+        #[cfg(feature = "y2022d11")]
+        d11::solution(),
+        #[cfg(feature = "y2022d12")]
+        d12::solution(),
+        #[cfg(feature = "y2022d13")]
+        d13::solution(),
+    ]
 }
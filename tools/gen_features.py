@@ -10,7 +10,8 @@ def main():
 
     # setup values
     year = args.year
-    days = list(range(1, 26))
+    # from 2025 onwards, seasons only have 12 days
+    days = list(range(1, 13 if int(year) >= 2025 else 26))
 
     # print defaults
     print("default = [...")
@@ -23,6 +24,9 @@ def main():
         if i % 5 == 4:
             print()
 
+    if len(days) % 5 != 0:
+        print()
+
     print("...]")
     print()
 